@@ -0,0 +1,86 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use zenoh_codec::{ZResult, ZStruct, ZStructEncode, ZWriterExt};
+
+// Each level stores its child with a prefixed size, which used to require computing the length
+// of the whole subtree at every level. The leaf may carry a payload.
+#[derive(ZStruct, PartialEq, Debug)]
+struct Node {
+    pub value: u32,
+
+    #[zenoh(size = prefixed)]
+    pub payload: Vec<u8>,
+
+    #[zenoh(presence = prefixed, size = prefixed)]
+    pub child: Option<Box<Node>>,
+}
+
+fn nested(depth: usize, payload: usize) -> Node {
+    let leaf = Node {
+        value: 0,
+        payload: vec![0xAA; payload],
+        child: None,
+    };
+
+    (0..depth).fold(leaf, |child, value| Node {
+        value: value as u32,
+        payload: Vec::new(),
+        child: Some(Box::new(child)),
+    })
+}
+
+// The encoder as derived before back-patching: each prefixed size is computed with `z_len`
+// before the field is encoded, so every level measures its whole subtree again.
+fn baseline<W: ZWriterExt>(node: &Node, w: &mut W) -> ZResult<()> {
+    <u32 as ZStructEncode>::z_encode(&node.value, w)?;
+    <usize as ZStructEncode>::z_encode(&node.payload.len(), w)?;
+    w.write_exact(&node.payload)?;
+    <u8 as ZStructEncode>::z_encode(&(node.child.is_some() as u8), w)?;
+
+    match &node.child {
+        Some(child) => {
+            <usize as ZStructEncode>::z_encode(&<_ as ZStructEncode>::z_len(child), w)?;
+            baseline(child, w)
+        }
+        None => Ok(()),
+    }
+}
+
+fn encode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("nested");
+    let mut data = vec![0u8; 1 << 16];
+    let mut expected = vec![0u8; 1 << 16];
+
+    for depth in [4, 16, 64] {
+        for payload in [0, 4096] {
+            let node = nested(depth, payload);
+            let id = format!("{depth}/{payload}");
+
+            baseline(&node, &mut expected.as_mut_slice()).unwrap();
+            <_ as ZStructEncode>::z_encode(&node, &mut data.as_mut_slice()).unwrap();
+            assert_eq!(data, expected);
+
+            group.bench_with_input(
+                BenchmarkId::new("z_len_per_level", &id),
+                &node,
+                |b, node| {
+                    b.iter(|| {
+                        let mut writer = data.as_mut_slice();
+                        baseline(node, &mut writer).unwrap();
+                    })
+                },
+            );
+
+            group.bench_with_input(BenchmarkId::new("z_encode", &id), &node, |b, node| {
+                b.iter(|| {
+                    let mut writer = data.as_mut_slice();
+                    <_ as ZStructEncode>::z_encode(node, &mut writer).unwrap();
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, encode);
+criterion_main!(benches);
@@ -0,0 +1,213 @@
+use proc_macro2::TokenStream;
+use syn::{DeriveInput, Ident, Type, spanned::Spanned};
+
+use crate::model::{generics, ty::ZenohType};
+
+mod fieldless;
+
+struct ZenohVariant {
+    ident: Ident,
+    ty: Type,
+}
+
+pub fn derive_zenum(input: DeriveInput) -> syn::Result<TokenStream> {
+    let data = match &input.data {
+        syn::Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "ZEnum can only be derived for enums",
+            ));
+        }
+    };
+
+    if data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "ZEnum requires at least one variant",
+        ));
+    }
+
+    if data
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, syn::Fields::Unit))
+    {
+        return fieldless::parse(&input, data);
+    }
+
+    let mut variants = Vec::<ZenohVariant>::new();
+    let mut unknown = Option::<Ident>::None;
+
+    for variant in &data.variants {
+        let field = match &variant.fields {
+            syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "ZEnum variants must wrap exactly one ZStruct with a header, or all be fieldless",
+                ));
+            }
+        };
+
+        if !is_unknown(variant)? {
+            variants.push(ZenohVariant {
+                ident: variant.ident.clone(),
+                ty: field.ty.clone(),
+            });
+            continue;
+        }
+
+        if unknown.is_some() {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "Only one variant can have the unknown attribute",
+            ));
+        }
+
+        if !matches!(ZenohType::from_type(&field.ty)?, ZenohType::ByteSlice) {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "The unknown variant must wrap a byte slice, such as &'a [u8]",
+            ));
+        }
+
+        unknown.replace(variant.ident.clone());
+    }
+
+    let ident = &input.ident;
+    let name = ident.to_string();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    let encode_generics = generics::bounded(
+        input.generics.clone(),
+        quote::quote! { zenoh_codec::ZStructEncode },
+    );
+    let (impl_generics, _, where_clause) = encode_generics.split_for_impl();
+
+    let lifetime = generics::lifetime(&input.generics);
+    let decode_generics = generics::bounded(
+        generics::with_lifetime(&input.generics, &lifetime),
+        quote::quote! { zenoh_codec::ZStructDecode<#lifetime> },
+    );
+    let (decode_impl_generics, _, decode_where_clause) = decode_generics.split_for_impl();
+
+    // Every variant, the unknown one included, encodes as the value it wraps.
+    let all = variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .chain(unknown.as_ref())
+        .collect::<Vec<_>>();
+
+    let dispatch = variants.iter().map(|ZenohVariant { ident, ty }| {
+        let field = ident.to_string();
+
+        quote::quote! {
+            if <#ty>::matches_header(header) {
+                return <#ty as zenoh_codec::ZStructDecode>::z_decode(r)
+                    .map(Self::#ident)
+                    .map_err(|e| zenoh_codec::ZReaderExt::trace(r, e, field_offset, #name, #field));
+            }
+        }
+    });
+
+    // A variant without fixed header values would match every header, and two variants whose
+    // fixed values agree on their common bits would match the same headers: the first one would
+    // always win the dispatch.
+    let mut checks = variants
+        .iter()
+        .map(|ZenohVariant { ident, ty }| {
+            let message =
+                format!("ZEnum variant `{ident}` wraps a ZStruct without fixed header values");
+
+            quote::quote_spanned! { ty.span() =>
+                const { assert!(<#ty>::HEADER_FIXED_MASK != 0, #message) };
+            }
+        })
+        .collect::<Vec<_>>();
+
+    for (i, a) in variants.iter().enumerate() {
+        for b in &variants[i + 1..] {
+            let (a_ident, a_ty, b_ident, b_ty) = (&a.ident, &a.ty, &b.ident, &b.ty);
+            let message =
+                format!("ZEnum variants `{a_ident}` and `{b_ident}` match the same headers");
+
+            checks.push(quote::quote_spanned! { b_ty.span() =>
+                const {
+                    assert!(
+                        (<#a_ty>::HEADER_BASE ^ <#b_ty>::HEADER_BASE)
+                            & <#a_ty>::HEADER_FIXED_MASK
+                            & <#b_ty>::HEADER_FIXED_MASK
+                            != 0,
+                        #message
+                    )
+                };
+            });
+        }
+    }
+
+    let fallback = match &unknown {
+        Some(unknown) => quote::quote! {
+            Ok(Self::#unknown(<_ as zenoh_codec::ZStructDecode>::z_decode(r)?))
+        },
+        None => quote::quote! {
+            let e = zenoh_codec::ZCodecError::UnknownMessage { header };
+            Err(zenoh_codec::ZReaderExt::trace(r, e, field_offset, #name, ""))
+        },
+    };
+
+    Ok(quote::quote! {
+        impl #impl_generics zenoh_codec::ZStructEncode for #ident #ty_generics #where_clause {
+            fn z_len(&self) -> usize {
+                match self {
+                    #(Self::#all(x) => <_ as zenoh_codec::ZStructEncode>::z_len(x),)*
+                }
+            }
+
+            fn z_encode<W: zenoh_codec::ZWriterExt>(&self, w: &mut W) -> zenoh_codec::ZResult<()> {
+                match self {
+                    #(Self::#all(x) => <_ as zenoh_codec::ZStructEncode>::z_encode(x, w),)*
+                }
+            }
+
+            fn z_encode_vectored<'s>(&'s self, w: &mut zenoh_codec::ZVectoredWriter<'s, '_>) -> zenoh_codec::ZResult<()> {
+                match self {
+                    #(Self::#all(x) => <_ as zenoh_codec::ZStructEncode>::z_encode_vectored(x, w),)*
+                }
+            }
+        }
+
+        impl #decode_impl_generics zenoh_codec::ZStructDecode<#lifetime> for #ident #ty_generics #decode_where_clause {
+            fn z_decode<R: zenoh_codec::ZReaderExt<#lifetime>>(r: &mut R) -> zenoh_codec::ZResult<Self> {
+                let field_offset = zenoh_codec::ZReaderExt::offset(r);
+                let header = zenoh_codec::ZReaderExt::peek_u8(r)
+                    .map_err(|e| zenoh_codec::ZReaderExt::trace(r, e, field_offset, #name, "header"))?;
+
+                #(#checks)*
+
+                #(#dispatch)*
+
+                #fallback
+            }
+        }
+    })
+}
+
+fn is_unknown(variant: &syn::Variant) -> syn::Result<bool> {
+    let mut unknown = false;
+
+    for attr in &variant.attrs {
+        if attr.path().is_ident("zenoh") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("unknown") {
+                    unknown = true;
+                    return Ok(());
+                }
+
+                Err(meta.error("Unsupported ZEnum variant attribute, expected `unknown`"))
+            })?;
+        }
+    }
+
+    Ok(unknown)
+}
@@ -0,0 +1,90 @@
+use proc_macro2::TokenStream;
+use syn::{DataEnum, DeriveInput};
+
+use crate::model::generics;
+
+/// Generates the conversions of a `#[repr(u8)]` fieldless enum, used as a header field or as a
+/// VLE body field.
+pub fn parse(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
+    let mut repr_u8 = false;
+
+    for attr in &input.attrs {
+        if attr.path().is_ident("repr") {
+            attr.parse_nested_meta(|meta| {
+                repr_u8 |= meta.path.is_ident("u8");
+                Ok(())
+            })?;
+        }
+    }
+
+    if !repr_u8 {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Fieldless ZEnum must be #[repr(u8)]",
+        ));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let lifetime = generics::lifetime(&input.generics);
+    let decode_generics = generics::with_lifetime(&input.generics, &lifetime);
+    let (decode_impl_generics, _, _) = decode_generics.split_for_impl();
+    let variants = data
+        .variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+
+    Ok(quote::quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Number of bits needed by the largest discriminant, checked against the width of
+            /// header slots holding this enum.
+            pub const Z_BITS: u32 = u8::BITS - (#(Self::#variants as u8)|*).leading_zeros();
+
+            const fn z_discriminant(&self) -> u8 {
+                match self {
+                    #(Self::#variants => Self::#variants as u8,)*
+                }
+            }
+        }
+
+        impl #impl_generics From<#ident #ty_generics> for u8 #where_clause {
+            fn from(value: #ident #ty_generics) -> u8 {
+                value.z_discriminant()
+            }
+        }
+
+        impl #impl_generics TryFrom<u8> for #ident #ty_generics #where_clause {
+            type Error = zenoh_codec::ZCodecError;
+
+            fn try_from(value: u8) -> zenoh_codec::ZResult<Self> {
+                #(
+                    if value == Self::#variants as u8 {
+                        return Ok(Self::#variants);
+                    }
+                )*
+
+                Err(zenoh_codec::ZCodecError::IntegerOutOfRange)
+            }
+        }
+
+        impl #impl_generics zenoh_codec::ZStructEncode for #ident #ty_generics #where_clause {
+            fn z_len(&self) -> usize {
+                <u64 as zenoh_codec::ZStructEncode>::z_len(&(self.z_discriminant() as u64))
+            }
+
+            fn z_encode<W: zenoh_codec::ZWriterExt>(&self, w: &mut W) -> zenoh_codec::ZResult<()> {
+                <u64 as zenoh_codec::ZStructEncode>::z_encode(&(self.z_discriminant() as u64), w)
+            }
+        }
+
+        impl #decode_impl_generics zenoh_codec::ZStructDecode<#lifetime> for #ident #ty_generics #where_clause {
+            fn z_decode<R: zenoh_codec::ZReaderExt<#lifetime>>(r: &mut R) -> zenoh_codec::ZResult<Self> {
+                let value = <u64 as zenoh_codec::ZStructDecode>::z_decode(r)?;
+                let value = <u8 as TryFrom<u64>>::try_from(value).map_err(|_| zenoh_codec::ZCodecError::IntegerOutOfRange)?;
+                <Self as TryFrom<u8>>::try_from(value)
+            }
+        }
+    })
+}
@@ -3,7 +3,8 @@ use quote::ToTokens;
 use syn::DeriveInput;
 
 use crate::{
-    model::{ZenohField, ZenohStruct, ty::ZenohType},
+    model::{ZenohField, ZenohStruct, attribute::EncodingAttribute, generics, ty::ZenohType},
+    owned,
     r#struct::{decode, encode, header, len},
 };
 
@@ -15,17 +16,38 @@ pub fn derive_zext(input: DeriveInput) -> syn::Result<TokenStream> {
     let r#struct = ZenohStruct::from_derive_input(&input)?;
     let ident = &r#struct.ident;
 
-    let generics = &r#struct.generics;
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = r#struct.generics.split_for_impl();
+
+    let encode_generics = generics::bounded(
+        r#struct.generics.clone(),
+        quote::quote! { zenoh_codec::ZStructEncode },
+    );
+    let (impl_generics, _, where_clause) = encode_generics.split_for_impl();
+
+    let lifetime = generics::lifetime(&r#struct.generics);
+    let decode_generics = generics::bounded(
+        generics::with_lifetime(&r#struct.generics, &lifetime),
+        quote::quote! { zenoh_codec::ZStructDecode<#lifetime> },
+    );
+    let (decode_impl_generics, _, decode_where_clause) = decode_generics.split_for_impl();
+
+    let ext_generics = generics::bounded(
+        decode_generics.clone(),
+        quote::quote! { zenoh_codec::ZStructEncode },
+    );
+    let (ext_impl_generics, _, ext_where_clause) = ext_generics.split_for_impl();
 
     let kind = infer_kind(&r#struct)?;
+    let owned = owned::parse(&input, &r#struct, true)?;
     if matches!(kind, InferredKind::U64) {
         let len = u64_len::parse(&r#struct);
         let encode = u64_encode::parse(&r#struct);
         let decode = u64_decode::parse(&r#struct);
 
         return Ok(quote::quote! {
-            impl<'a> zenoh_codec::ZExt<'a> for #ident #ty_generics #where_clause {
+            #owned
+
+            impl #ext_impl_generics zenoh_codec::ZExt<#lifetime> for #ident #ty_generics #ext_where_clause {
                 const KIND: zenoh_codec::ZExtKind = #kind;
             }
 
@@ -34,15 +56,19 @@ pub fn derive_zext(input: DeriveInput) -> syn::Result<TokenStream> {
                     #len
                 }
 
-                fn z_encode(&self, w: &mut zenoh_codec::ZWriter) -> zenoh_codec::ZResult<()> {
-                    #encode
+                fn z_encode<W: zenoh_codec::ZWriterExt>(&self, w: &mut W) -> zenoh_codec::ZResult<()> {
+                    let result = (move || {
+                        #encode
 
-                    Ok(())
+                        Ok(())
+                    })();
+
+                    zenoh_codec::z_encode_sized(self, result)
                 }
             }
 
-            impl<'a> zenoh_codec::ZStructDecode<'a> for #ident #ty_generics #where_clause {
-                fn z_decode(r: &mut zenoh_codec::ZReader<'a>) -> zenoh_codec::ZResult<Self> {
+            impl #decode_impl_generics zenoh_codec::ZStructDecode<#lifetime> for #ident #ty_generics #decode_where_clause {
+                fn z_decode<R: zenoh_codec::ZReaderExt<#lifetime>>(r: &mut R) -> zenoh_codec::ZResult<Self> {
                     #decode
                 }
             }
@@ -52,13 +78,16 @@ pub fn derive_zext(input: DeriveInput) -> syn::Result<TokenStream> {
     let header = header::parse(&r#struct)?;
 
     let len = len::parse(&r#struct)?;
-    let encode = encode::parse(&r#struct)?;
+    let encode = encode::parse(&r#struct, false)?;
+    let encode_vectored = encode::parse(&r#struct, true)?;
     let decode = decode::parse(&r#struct)?;
 
     Ok(quote::quote! {
         #header
 
-        impl<'a> zenoh_codec::ZExt<'a> for #ident #ty_generics #where_clause {
+        #owned
+
+        impl #ext_impl_generics zenoh_codec::ZExt<#lifetime> for #ident #ty_generics #ext_where_clause {
             const KIND: zenoh_codec::ZExtKind = #kind;
         }
 
@@ -67,15 +96,29 @@ pub fn derive_zext(input: DeriveInput) -> syn::Result<TokenStream> {
                 #len
             }
 
-            fn z_encode(&self, w: &mut zenoh_codec::ZWriter) -> zenoh_codec::ZResult<()> {
-                #encode
+            fn z_encode<W: zenoh_codec::ZWriterExt>(&self, w: &mut W) -> zenoh_codec::ZResult<()> {
+                let result = (move || {
+                    #encode
 
-                Ok(())
+                    Ok(())
+                })();
+
+                zenoh_codec::z_encode_sized(self, result)
+            }
+
+            fn z_encode_vectored<'s>(&'s self, w: &mut zenoh_codec::ZVectoredWriter<'s, '_>) -> zenoh_codec::ZResult<()> {
+                let result = (move || {
+                    #encode_vectored
+
+                    Ok(())
+                })();
+
+                zenoh_codec::z_encode_sized(self, result)
             }
         }
 
-        impl<'a> zenoh_codec::ZStructDecode<'a> for #ident #ty_generics #where_clause {
-            fn z_decode(r: &mut zenoh_codec::ZReader<'a>) -> zenoh_codec::ZResult<Self> {
+        impl #decode_impl_generics zenoh_codec::ZStructDecode<#lifetime> for #ident #ty_generics #decode_where_clause {
+            fn z_decode<R: zenoh_codec::ZReaderExt<#lifetime>>(r: &mut R) -> zenoh_codec::ZResult<Self> {
                 #decode
             }
         }
@@ -111,12 +154,23 @@ fn infer_kind(ext: &ZenohStruct) -> syn::Result<InferredKind> {
                 Span::call_site(),
                 "Cannot infer ZExtKind from only one ext block field",
             )),
+            // Fixed-width integers are not VLE, their body is sized as a `ZStruct`.
+            ZenohField::Regular { field }
+                if !matches!(field.attr.encoding, EncodingAttribute::None) =>
+            {
+                Ok(InferredKind::ZStruct)
+            }
             ZenohField::Regular { field } => match field.ty {
                 ZenohType::U8
                 | ZenohType::U16
                 | ZenohType::U32
                 | ZenohType::U64
-                | ZenohType::USize => Ok(InferredKind::U64),
+                | ZenohType::USize
+                | ZenohType::I8
+                | ZenohType::I16
+                | ZenohType::I32
+                | ZenohType::I64
+                | ZenohType::ISize => Ok(InferredKind::U64),
                 _ => Ok(InferredKind::ZStruct),
             },
         }
@@ -1,6 +1,10 @@
 use proc_macro2::TokenStream;
 
-use crate::model::{ZenohField, ZenohStruct, ty::ZenohType};
+use crate::{
+    model::{ZenohField, ZenohStruct},
+    r#struct::decode::{field_name, traced},
+};
+
 pub fn parse(r#struct: &ZenohStruct) -> TokenStream {
     let field = r#struct
         .fields
@@ -15,20 +19,28 @@ pub fn parse(r#struct: &ZenohStruct) -> TokenStream {
     };
 
     let access = &field.access;
-    let ty = &field.ty;
-    let ty = match ty {
-        ZenohType::U8 => quote::quote! { u8 },
-        ZenohType::U16 => quote::quote! { u16 },
-        ZenohType::U32 => quote::quote! { u32 },
-        ZenohType::U64 => quote::quote! { u64 },
-        ZenohType::USize => quote::quote! { usize },
-        _ => unreachable!(),
+    let binding = &field.binding;
+    let ty = field
+        .ty
+        .int_type()
+        .expect("U64 extensions have a single integer field, this should have been caught earlier");
+
+    let stmts = match field.ty.is_signed() {
+        true => quote::quote! {
+            let #binding = < #ty as zenoh_codec::ZStructDecode>::z_decode(r)?;
+        },
+        false => quote::quote! {
+            let #binding = < u64 as zenoh_codec::ZStructDecode>::z_decode(r)?;
+            let #binding = <#ty as TryFrom<u64>>::try_from(#binding).map_err(|_| zenoh_codec::ZCodecError::IntegerOutOfRange)?;
+        },
     };
 
+    let decode = traced(r#struct, binding, &field_name(access), &[stmts]);
+
     quote::quote! {
-        let #access = < u64 as zenoh_codec::ZStructDecode>::z_decode(r)? as #ty;
+        #decode
         Ok(Self {
-            #access
+            #access: #binding
         })
     }
 }
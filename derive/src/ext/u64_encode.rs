@@ -17,6 +17,13 @@ pub fn parse(r#struct: &ZenohStruct) -> TokenStream {
 
     let access = &field.access;
 
+    // Signed integers are already encoded as zig-zag VLE.
+    if field.ty.is_signed() {
+        return quote::quote! {
+            < _ as zenoh_codec::ZStructEncode>::z_encode(&self. #access, w)?;
+        };
+    }
+
     quote::quote! {
         < u64 as zenoh_codec::ZStructEncode>::z_encode(&(self. #access as u64), w)?;
     }
@@ -17,6 +17,12 @@ pub fn parse(r#struct: &ZenohStruct) -> TokenStream {
 
     let access = &field.access;
 
+    if field.ty.is_signed() {
+        return quote::quote! {
+            < _ as zenoh_codec::ZStructEncode>::z_len(&self. #access)
+        };
+    }
+
     quote::quote! {
         < u64 as zenoh_codec::ZStructEncode>::z_len(&(self. #access as u64))
     }
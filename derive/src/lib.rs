@@ -1,6 +1,8 @@
 pub(crate) mod model;
 
+pub(crate) mod r#enum;
 pub(crate) mod ext;
+pub(crate) mod owned;
 pub(crate) mod r#struct;
 
 #[proc_macro_derive(ZStruct, attributes(zenoh))]
@@ -20,3 +22,12 @@ pub fn derive_zext(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+#[proc_macro_derive(ZEnum, attributes(zenoh))]
+pub fn derive_zenum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+
+    r#enum::derive_zenum(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
@@ -1,35 +1,64 @@
 use proc_macro2::TokenStream;
-use syn::{Generics, Ident, LitStr};
+use quote::format_ident;
+use syn::{Generics, Ident, LitStr, ext::IdentExt};
 
 use crate::model::{
     attribute::{ExtAttribute, ZenohAttribute},
+    fill::Fill,
+    flags::FlagsByte,
     ty::ZenohType,
 };
 
 pub mod attribute;
+pub mod fill;
+pub mod flags;
+pub mod generics;
 pub mod ty;
 
 pub struct ZenohFieldInner {
     pub attr: ZenohAttribute,
     pub ty: ZenohType,
+    pub raw_ty: syn::Type,
+    /// Member of the struct, `name` or `0` for tuple structs.
     pub access: TokenStream,
+    /// Local variable holding the field while decoding, `name` or `_0` for tuple structs.
+    pub binding: TokenStream,
+    /// Flags byte declared by this field, see `flags::FlagsByte`.
+    pub flags: Option<FlagsByte>,
+    /// How this field is filled on encode when a later field references it, see `fill::Fill`.
+    pub fill: Option<Fill>,
 }
 
 impl ZenohFieldInner {
-    pub fn from_field(field: &syn::Field) -> syn::Result<Self> {
+    pub fn from_field(field: &syn::Field, index: usize) -> syn::Result<Self> {
         let attr = ZenohAttribute::from_field(field)?;
 
-        let ident = field
-            .ident
-            .as_ref()
-            .ok_or_else(|| syn::Error::new_spanned(field, "Expected named field"))?;
-
-        let access = quote::quote! { #ident };
+        let (access, binding) = match &field.ident {
+            Some(ident) => (quote::quote! { #ident }, quote::quote! { #ident }),
+            None => {
+                let index = syn::Index::from(index);
+                let binding = format_ident!("_{}", index);
+                (quote::quote! { #index }, quote::quote! { #binding })
+            }
+        };
 
         let ty = ZenohType::from_type(&field.ty)?;
         ty.check_attribute(&attr)?;
 
-        Ok(Self { attr, access, ty })
+        let flags = attr
+            .flags
+            .clone()
+            .map(|expr| FlagsByte::new(expr, &binding.to_string()));
+
+        Ok(Self {
+            attr,
+            ty,
+            raw_ty: field.ty.clone(),
+            access,
+            binding,
+            flags,
+            fill: None,
+        })
     }
 }
 
@@ -46,6 +75,7 @@ pub struct ZenohStruct {
     pub ident: Ident,
     pub generics: Generics,
     pub header: Option<HeaderDeclaration>,
+    pub owned: Option<Ident>,
     pub fields: Vec<ZenohField>,
 }
 
@@ -64,8 +94,8 @@ impl ZenohStruct {
         let mut fields_vec = Vec::new();
         let mut found_ext_block = false;
         let mut in_ext_block = false;
-        for field in fields {
-            let field = ZenohFieldInner::from_field(field)?;
+        for (index, field) in fields.iter().enumerate() {
+            let field = ZenohFieldInner::from_field(field, index)?;
             let is_ext = !matches!(field.attr.ext, ExtAttribute::None);
             if is_ext {
                 if !found_ext_block {
@@ -100,6 +130,7 @@ impl ZenohStruct {
         }
 
         let mut header = Option::<HeaderDeclaration>::None;
+        let mut owned = Option::<Ident>::None;
 
         for attr in &input.attrs {
             if attr.path().is_ident("zenoh") {
@@ -108,6 +139,9 @@ impl ZenohStruct {
                         let value = meta.value()?;
                         let expr: LitStr = value.parse()?;
                         header.replace(HeaderDeclaration { expr });
+                    } else if meta.path.is_ident("owned") {
+                        let value = meta.value()?;
+                        owned.replace(value.call(Ident::parse_any)?);
                     }
 
                     Ok(())
@@ -115,10 +149,14 @@ impl ZenohStruct {
             }
         }
 
+        flags::resolve(&mut fields_vec, header.as_ref().map(|header| &header.expr))?;
+        fill::resolve(&mut fields_vec)?;
+
         Ok(Self {
             ident: input.ident.clone(),
             generics: input.generics.clone(),
             header,
+            owned,
             fields: fields_vec,
         })
     }
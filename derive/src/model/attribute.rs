@@ -1,5 +1,6 @@
 use proc_macro2::{Span, TokenStream};
-use syn::{Expr, Ident, meta::ParseNestedMeta, parenthesized, spanned::Spanned};
+use quote::ToTokens;
+use syn::{Expr, Ident, LitStr, meta::ParseNestedMeta, parenthesized, spanned::Spanned};
 
 #[derive(Clone)]
 pub struct ZenohAttribute {
@@ -12,6 +13,8 @@ pub struct ZenohAttribute {
     pub header: HeaderAttribute,
     pub ext: ExtAttribute,
     pub default: DefaultAttribute,
+    pub encoding: EncodingAttribute,
+    pub flags: Option<LitStr>,
 }
 
 impl Default for ZenohAttribute {
@@ -25,6 +28,8 @@ impl Default for ZenohAttribute {
             header: HeaderAttribute::default(),
             ext: ExtAttribute::default(),
             default: DefaultAttribute::default(),
+            encoding: EncodingAttribute::default(),
+            flags: None,
         }
     }
 }
@@ -32,7 +37,10 @@ impl Default for ZenohAttribute {
 impl ZenohAttribute {
     pub fn from_field(field: &syn::Field) -> syn::Result<Self> {
         let mut zattr = ZenohAttribute {
-            span: field.ident.span(),
+            span: match &field.ident {
+                Some(ident) => ident.span(),
+                None => field.ty.span(),
+            },
             ..Default::default()
         };
 
@@ -46,6 +54,8 @@ impl ZenohAttribute {
                     let header = HeaderAttribute::from_meta(&meta)?;
                     let default = DefaultAttribute::from_meta(&meta)?;
                     let ext = ExtAttribute::from_meta(&meta)?;
+                    let encoding = EncodingAttribute::from_meta(&meta)?;
+                    let flags = flags_from_meta(&meta)?;
 
                     if !matches!(size, SizeAttribute::None) {
                         zattr.size = size;
@@ -68,6 +78,12 @@ impl ZenohAttribute {
                     if !matches!(default, DefaultAttribute::None) {
                         zattr.default = default;
                     }
+                    if !matches!(encoding, EncodingAttribute::None) {
+                        zattr.encoding = encoding;
+                    }
+                    if flags.is_some() {
+                        zattr.flags = flags;
+                    }
 
                     Ok(())
                 })?;
@@ -94,9 +110,57 @@ fn mandatory_from_meta(meta: &ParseNestedMeta) -> syn::Result<bool> {
     Ok(false)
 }
 
-fn ident_to_header_path(ident: &Ident) -> TokenStream {
-    let ident = syn::Ident::new(&format!("HEADER_SLOT_{}", ident), ident.span());
-    quote::quote! { Self::#ident }
+fn flags_from_meta(meta: &ParseNestedMeta) -> syn::Result<Option<LitStr>> {
+    if meta.path.is_ident("flags") {
+        return Ok(Some(meta.value()?.parse()?));
+    }
+
+    Ok(None)
+}
+
+/// A named slot of the header, or of a flags byte declared by an earlier field. It expands to
+/// the mask of the slot, `Self::HEADER_SLOT_<name>`.
+#[derive(Clone)]
+pub struct HeaderSlot {
+    pub name: Ident,
+    /// Variable holding the byte of the slot while encoding and decoding.
+    pub byte: Ident,
+}
+
+impl HeaderSlot {
+    fn new(name: Ident) -> Self {
+        Self {
+            name,
+            byte: Ident::new("header", Span::call_site()),
+        }
+    }
+}
+
+impl ToTokens for HeaderSlot {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let ident = Ident::new(&format!("HEADER_SLOT_{}", self.name), self.name.span());
+        tokens.extend(quote::quote! { Self::#ident });
+    }
+}
+
+/// An earlier field referenced by `size = field(name)` or `presence = field(name)`.
+#[derive(Clone)]
+pub struct FieldRef {
+    pub member: syn::Member,
+    /// Local variable holding the referenced field while decoding, set once fields are resolved.
+    pub binding: TokenStream,
+}
+
+impl FieldRef {
+    fn parse(value: syn::parse::ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in value);
+
+        Ok(Self {
+            member: content.parse()?,
+            binding: TokenStream::new(),
+        })
+    }
 }
 
 #[derive(Clone, Default)]
@@ -105,7 +169,8 @@ pub enum SizeAttribute {
     None,
     Prefixed,
     Remain,
-    Header(TokenStream),
+    Header(HeaderSlot),
+    Field(FieldRef),
 }
 
 impl SizeAttribute {
@@ -121,7 +186,9 @@ impl SizeAttribute {
                 let content;
                 parenthesized!(content in value);
                 let ident: Ident = content.parse()?;
-                return Ok(SizeAttribute::Header(ident_to_header_path(&ident)));
+                return Ok(SizeAttribute::Header(HeaderSlot::new(ident)));
+            } else if size == "field" {
+                return Ok(SizeAttribute::Field(FieldRef::parse(value)?));
             } else {
                 return Err(syn::Error::new_spanned(
                     size,
@@ -139,7 +206,8 @@ pub enum PresenceAttribute {
     #[default]
     None,
     Prefixed,
-    Header(TokenStream),
+    Header(HeaderSlot),
+    Field(FieldRef),
 }
 
 impl PresenceAttribute {
@@ -153,7 +221,9 @@ impl PresenceAttribute {
                 let content;
                 parenthesized!(content in value);
                 let ident: Ident = content.parse()?;
-                return Ok(PresenceAttribute::Header(ident_to_header_path(&ident)));
+                return Ok(PresenceAttribute::Header(HeaderSlot::new(ident)));
+            } else if presence == "field" {
+                return Ok(PresenceAttribute::Field(FieldRef::parse(value)?));
             } else {
                 return Err(syn::Error::new_spanned(
                     presence,
@@ -170,14 +240,14 @@ impl PresenceAttribute {
 pub enum HeaderAttribute {
     #[default]
     None,
-    Mask(TokenStream),
+    Mask(HeaderSlot),
 }
 
 impl HeaderAttribute {
     fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
         if meta.path.is_ident("header") {
             let ident: Ident = meta.value()?.parse()?;
-            return Ok(HeaderAttribute::Mask(ident_to_header_path(&ident)));
+            return Ok(HeaderAttribute::Mask(HeaderSlot::new(ident)));
         }
 
         Ok(HeaderAttribute::None)
@@ -207,9 +277,36 @@ pub enum DefaultAttribute {
     #[default]
     None,
     Expr(Expr),
+    /// Default of an owned companion field: `expr` is the borrowed default of type `ty`.
+    Owned {
+        expr: Expr,
+        ty: Box<syn::Type>,
+    },
 }
 
 impl DefaultAttribute {
+    /// Whether `value` differs from the default, without building an owned default.
+    pub fn differs(&self, value: TokenStream) -> Option<TokenStream> {
+        match self {
+            DefaultAttribute::None => None,
+            DefaultAttribute::Expr(expr) => Some(quote::quote! { &#value != &#expr }),
+            DefaultAttribute::Owned { expr, ty } => Some(quote::quote! {
+                <#ty as zenoh_codec::ZIntoOwned<'_>>::as_borrowed(&#value) != #expr
+            }),
+        }
+    }
+
+    /// The default value itself, used to initialize decoded fields.
+    pub fn value(&self) -> Option<TokenStream> {
+        match self {
+            DefaultAttribute::None => None,
+            DefaultAttribute::Expr(expr) => Some(quote::quote! { #expr }),
+            DefaultAttribute::Owned { expr, .. } => Some(quote::quote! {
+                zenoh_codec::ZIntoOwned::into_owned(#expr)
+            }),
+        }
+    }
+
     fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
         if meta.path.is_ident("default") {
             let expr: Expr = meta.value()?.parse()?;
@@ -219,3 +316,32 @@ impl DefaultAttribute {
         Ok(DefaultAttribute::None)
     }
 }
+
+/// Encoding of integer fields: VLE by default, or fixed-width.
+#[derive(Clone, Default)]
+pub enum EncodingAttribute {
+    #[default]
+    None,
+    Le,
+    Be,
+}
+
+impl EncodingAttribute {
+    fn from_meta(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        if meta.path.is_ident("encoding") {
+            let encoding: syn::Ident = meta.value()?.parse()?;
+            if encoding == "le" {
+                return Ok(EncodingAttribute::Le);
+            } else if encoding == "be" {
+                return Ok(EncodingAttribute::Be);
+            } else {
+                return Err(syn::Error::new_spanned(
+                    encoding,
+                    "Invalid encoding attribute value, expected `le` or `be`",
+                ));
+            }
+        }
+
+        Ok(EncodingAttribute::None)
+    }
+}
@@ -0,0 +1,151 @@
+use proc_macro2::TokenStream;
+
+use crate::model::{
+    ZenohField,
+    attribute::{FieldRef, PresenceAttribute, SizeAttribute},
+    ty::ZenohType,
+};
+
+/// How a field referenced by `size = field(..)` or `presence = field(..)` is filled on encode,
+/// from the later field that references it.
+pub enum Fill {
+    Size { access: TokenStream, optional: bool },
+    Presence { access: TokenStream },
+}
+
+impl Fill {
+    /// Value encoded in place of the referenced field, of type `raw_ty`.
+    pub fn value(&self, ty: &ZenohType, raw_ty: &syn::Type) -> TokenStream {
+        match self {
+            Fill::Size { .. } => {
+                let len = self.raw();
+                quote::quote! {
+                    <#raw_ty as TryFrom<usize>>::try_from(#len).map_err(|_| zenoh_codec::ZCodecError::IntegerOutOfRange)?
+                }
+            }
+            Fill::Presence { access } => match ty {
+                ZenohType::Bool => quote::quote! { self. #access.is_some() },
+                _ => quote::quote! { self. #access.is_some() as #raw_ty },
+            },
+        }
+    }
+
+    /// Value as a `usize`, used to compute the length of a VLE without failing.
+    pub fn raw(&self) -> TokenStream {
+        match self {
+            Fill::Size {
+                access,
+                optional: false,
+            } => quote::quote! {
+                < _ as zenoh_codec::ZStructEncode>::z_len(&self. #access)
+            },
+            Fill::Size {
+                access,
+                optional: true,
+            } => quote::quote! {
+                match &self. #access {
+                    Some(inner) => < _ as zenoh_codec::ZStructEncode>::z_len(inner),
+                    None => 0usize,
+                }
+            },
+            Fill::Presence { access } => quote::quote! {
+                self. #access.is_some() as usize
+            },
+        }
+    }
+}
+
+/// Resolves `size = field(..)` and `presence = field(..)` references, which must point to an
+/// earlier integer (or `bool`, for presence) field. That field is then filled on encode.
+pub fn resolve(fields: &mut [ZenohField]) -> syn::Result<()> {
+    let mut members = Vec::<Option<(String, TokenStream)>>::new();
+    let mut fills = Vec::<(usize, Fill, syn::Member)>::new();
+
+    for (index, field) in fields.iter_mut().enumerate() {
+        let ZenohField::Regular { field } = field else {
+            members.push(None);
+            continue;
+        };
+
+        members.push(Some((field.access.to_string(), field.binding.clone())));
+
+        let access = field.access.clone();
+        let optional = matches!(field.ty, ZenohType::Option(_));
+
+        let size = match &mut field.attr.size {
+            SizeAttribute::Field(r) => Some((r, Fill::Size { access, optional })),
+            _ => None,
+        };
+        let presence = match &mut field.attr.presence {
+            PresenceAttribute::Field(r) => Some((
+                r,
+                Fill::Presence {
+                    access: field.access.clone(),
+                },
+            )),
+            _ => None,
+        };
+
+        for (r, fill) in [size, presence].into_iter().flatten() {
+            let name = member_name(&r.member);
+            let owner = members
+                .iter()
+                .position(|member| matches!(member, Some((access, _)) if *access == name));
+
+            let Some(owner) = owner.filter(|owner| *owner < index) else {
+                return Err(error(r, "must reference an earlier field of the struct"));
+            };
+
+            r.binding = members[owner].as_ref().unwrap().1.clone();
+            fills.push((owner, fill, r.member.clone()));
+        }
+    }
+
+    for (owner, fill, member) in fills {
+        let ZenohField::Regular { field } = &mut fields[owner] else {
+            unreachable!("Only regular fields can be referenced");
+        };
+
+        let supported = match &fill {
+            Fill::Size { .. } => matches!(
+                field.ty,
+                ZenohType::U8 | ZenohType::U16 | ZenohType::U32 | ZenohType::U64 | ZenohType::USize
+            ),
+            Fill::Presence { .. } => matches!(
+                field.ty,
+                ZenohType::Bool
+                    | ZenohType::U8
+                    | ZenohType::U16
+                    | ZenohType::U32
+                    | ZenohType::U64
+                    | ZenohType::USize
+            ),
+        };
+
+        if !supported || field.flags.is_some() {
+            return Err(syn::Error::new_spanned(
+                member,
+                "size = field(..) must reference an unsigned integer field, presence = field(..) an unsigned integer or bool field",
+            ));
+        }
+
+        if field.fill.is_some() {
+            return Err(syn::Error::new_spanned(
+                member,
+                "A field can only be referenced by a single size or presence attribute",
+            ));
+        }
+
+        field.fill = Some(fill);
+    }
+
+    Ok(())
+}
+
+fn error(r: &FieldRef, message: &str) -> syn::Error {
+    syn::Error::new_spanned(&r.member, format!("'{}' {message}", member_name(&r.member)))
+}
+
+fn member_name(member: &syn::Member) -> String {
+    quote::quote! { #member }.to_string()
+}
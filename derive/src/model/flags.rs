@@ -0,0 +1,132 @@
+use proc_macro2::Span;
+use syn::{Ident, LitStr};
+
+use crate::model::{
+    ZenohField,
+    attribute::{HeaderAttribute, HeaderSlot, PresenceAttribute, SizeAttribute},
+};
+
+/// A flags byte declared by `#[zenoh(flags = "...")]` on a `u8` field, laid out like the header.
+pub struct FlagsByte {
+    pub expr: LitStr,
+    /// Variable holding the byte while encoding and decoding.
+    pub byte: Ident,
+    /// Slots of this byte filled by later fields, the field itself holds the other bits.
+    pub driven: Vec<HeaderSlot>,
+}
+
+impl FlagsByte {
+    pub fn new(expr: LitStr, binding: &str) -> Self {
+        let byte = Ident::new(
+            &format!("flags_{}", binding.trim_start_matches("r#")),
+            Span::call_site(),
+        );
+
+        Self {
+            expr,
+            byte,
+            driven: Vec::new(),
+        }
+    }
+}
+
+/// Names of the slots declared by a header or flags layout, which all become `HEADER_SLOT_<name>`
+/// constants of the struct.
+fn slot_names(expr: &LitStr) -> Vec<String> {
+    expr.value()
+        .split('|')
+        .filter_map(|part| {
+            let name = part.split(['=', ':']).next().unwrap_or_default().trim();
+            (!name.is_empty() && name != "_").then(|| name.to_owned())
+        })
+        .collect()
+}
+
+/// Points the slots used by `size`, `presence` and `header` attributes to the flags byte that
+/// declares them, if any. That byte must be declared by an earlier field.
+///
+/// Slot names must be unique across the header and all flags bytes.
+pub fn resolve(fields: &mut [ZenohField], header: Option<&LitStr>) -> syn::Result<()> {
+    let mut names = Vec::<String>::new();
+    let mut declared = Vec::<(usize, String, Ident)>::new();
+
+    let layouts = fields
+        .iter()
+        .enumerate()
+        .filter_map(|(index, field)| match field {
+            ZenohField::Regular { field } => field
+                .flags
+                .as_ref()
+                .map(|flags| (&flags.expr, Some((index, &flags.byte)))),
+            _ => None,
+        });
+
+    for (expr, owner) in header.map(|expr| (expr, None)).into_iter().chain(layouts) {
+        for name in slot_names(expr) {
+            if names.contains(&name) {
+                return Err(syn::Error::new(
+                    expr.span(),
+                    format!("Slot '{name}' is declared more than once in the header and flags"),
+                ));
+            }
+
+            names.push(name.clone());
+            if let Some((index, byte)) = owner {
+                declared.push((index, name, byte.clone()));
+            }
+        }
+    }
+
+    let mut driven = Vec::<(usize, HeaderSlot)>::new();
+
+    for (index, field) in fields.iter_mut().enumerate() {
+        let ZenohField::Regular { field } = field else {
+            continue;
+        };
+
+        let attr = &mut field.attr;
+        let size = match &mut attr.size {
+            SizeAttribute::Header(slot) => Some(slot),
+            _ => None,
+        };
+        let presence = match &mut attr.presence {
+            PresenceAttribute::Header(slot) => Some(slot),
+            _ => None,
+        };
+        let header = match &mut attr.header {
+            HeaderAttribute::Mask(slot) => Some(slot),
+            _ => None,
+        };
+
+        // Slot names are unique, so a slot is declared by a single flags byte at most.
+        for slot in [size, presence, header].into_iter().flatten() {
+            let Some((owner, _, byte)) = declared.iter().find(|(_, name, _)| slot.name == name)
+            else {
+                continue;
+            };
+
+            if *owner >= index {
+                return Err(syn::Error::new(
+                    slot.name.span(),
+                    format!(
+                        "Slot '{}' must be declared by the flags of an earlier field",
+                        slot.name
+                    ),
+                ));
+            }
+
+            slot.byte = byte.clone();
+            driven.push((*owner, slot.clone()));
+        }
+    }
+
+    for (owner, slot) in driven {
+        if let ZenohField::Regular { field } = &mut fields[owner]
+            && let Some(flags) = &mut field.flags
+        {
+            flags.driven.push(slot);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use proc_macro2::{Span, TokenStream};
+use syn::{GenericParam, Generics, Lifetime, LifetimeParam};
+
+/// Lifetime the decoded value borrows from: the struct's own lifetime, or `'a` when it has none.
+pub fn lifetime(generics: &Generics) -> Lifetime {
+    generics
+        .lifetimes()
+        .next()
+        .map(|param| param.lifetime.clone())
+        .unwrap_or_else(|| Lifetime::new("'a", Span::call_site()))
+}
+
+/// Generics of the `ZStructDecode` and `ZExt` impls, declaring `lifetime` if the struct does not.
+pub fn with_lifetime(generics: &Generics, lifetime: &Lifetime) -> Generics {
+    let mut generics = generics.clone();
+
+    if generics.lifetimes().next().is_none() {
+        let param = LifetimeParam::new(lifetime.clone());
+        generics.params.insert(0, GenericParam::Lifetime(param));
+    }
+
+    generics
+}
+
+/// Requires every type parameter to implement `bound`, e.g. `zenoh_codec::ZStructEncode`.
+pub fn bounded(mut generics: Generics, bound: TokenStream) -> Generics {
+    let params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect::<Vec<_>>();
+
+    if params.is_empty() {
+        return generics;
+    }
+
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause
+            .predicates
+            .push(syn::parse_quote! { #param: #bound });
+    }
+
+    generics
+}
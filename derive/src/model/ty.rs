@@ -1,8 +1,10 @@
-use syn::{Type, TypeArray, TypeReference};
+use syn::{PathSegment, Type, TypeArray, TypeReference, TypeSlice};
+
+use proc_macro2::TokenStream;
 
 use crate::model::attribute::{
-    DefaultAttribute, ExtAttribute, HeaderAttribute, PresenceAttribute, SizeAttribute,
-    ZenohAttribute,
+    DefaultAttribute, EncodingAttribute, ExtAttribute, HeaderAttribute, PresenceAttribute,
+    SizeAttribute, ZenohAttribute,
 };
 
 pub enum ZenohType {
@@ -12,17 +14,52 @@ pub enum ZenohType {
     U64,
     USize,
 
+    I8,
+    I16,
+    I32,
+    I64,
+    ISize,
+
+    Bool,
+    F32,
+    F64,
+
     ByteArray,
 
     ByteSlice,
     Str,
 
     ZStruct,
+    Seq,
 
     Option(Box<ZenohType>),
 }
 
 impl ZenohType {
+    /// The primitive type of integer fields.
+    pub fn int_type(&self) -> Option<TokenStream> {
+        match self {
+            ZenohType::U8 => Some(quote::quote! { u8 }),
+            ZenohType::U16 => Some(quote::quote! { u16 }),
+            ZenohType::U32 => Some(quote::quote! { u32 }),
+            ZenohType::U64 => Some(quote::quote! { u64 }),
+            ZenohType::USize => Some(quote::quote! { usize }),
+            ZenohType::I8 => Some(quote::quote! { i8 }),
+            ZenohType::I16 => Some(quote::quote! { i16 }),
+            ZenohType::I32 => Some(quote::quote! { i32 }),
+            ZenohType::I64 => Some(quote::quote! { i64 }),
+            ZenohType::ISize => Some(quote::quote! { isize }),
+            _ => None,
+        }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(
+            self,
+            ZenohType::I8 | ZenohType::I16 | ZenohType::I32 | ZenohType::I64 | ZenohType::ISize
+        )
+    }
+
     pub fn check_attribute(&self, attr: &ZenohAttribute) -> syn::Result<()> {
         let (s, me, m, p, h, e, d) = (
             !matches!(attr.size, SizeAttribute::None),
@@ -34,12 +71,38 @@ impl ZenohType {
             !matches!(attr.default, DefaultAttribute::None),
         );
 
+        if !matches!(attr.encoding, EncodingAttribute::None)
+            && !matches!(
+                self,
+                ZenohType::U16
+                    | ZenohType::U32
+                    | ZenohType::U64
+                    | ZenohType::I16
+                    | ZenohType::I32
+                    | ZenohType::I64
+            )
+        {
+            return Err(syn::Error::new(
+                attr.span,
+                "encoding attribute is only supported by u16, u32, u64, i16, i32 and i64 types",
+            ));
+        }
+
+        if attr.flags.is_some()
+            && (!matches!(self, ZenohType::U8) || s || me || m || p || h || e || d)
+        {
+            return Err(syn::Error::new(
+                attr.span,
+                "flags attribute is only supported by u8 types, without other attributes",
+            ));
+        }
+
         match self {
-            ZenohType::U8 => {
+            ZenohType::U8 | ZenohType::I8 | ZenohType::Bool => {
                 if s || me || m || p || e || d {
                     return Err(syn::Error::new(
                         attr.span,
-                        "u8 type does not support size, maybe_empty, mandatory, presence, ext, or default attributes",
+                        "u8, i8 and bool types do not support size, maybe_empty, mandatory, presence, ext, or default attributes",
                     ));
                 }
                 Ok(())
@@ -48,11 +111,17 @@ impl ZenohType {
             | ZenohType::U32
             | ZenohType::U64
             | ZenohType::USize
+            | ZenohType::I16
+            | ZenohType::I32
+            | ZenohType::I64
+            | ZenohType::ISize
+            | ZenohType::F32
+            | ZenohType::F64
             | ZenohType::ByteArray => {
                 if s || me || m || p || h || e || d {
                     return Err(syn::Error::new(
                         attr.span,
-                        "u16, u32, u64, usize and [u8; N] types do not support size, maybe_empty, mandatory, presence, header, ext, or default attributes",
+                        "u16, u32, u64, usize, signed integers above i8, floats and [u8; N] types do not support size, maybe_empty, mandatory, presence, header, ext, or default attributes",
                     ));
                 }
                 Ok(())
@@ -99,7 +168,29 @@ impl ZenohType {
                 }
                 Ok(())
             }
+            ZenohType::Seq => {
+                if me || m || p || h || e || d {
+                    return Err(syn::Error::new(
+                        attr.span,
+                        "ZSeq type does not support maybe_empty, mandatory, presence, header, ext, or default attributes",
+                    ));
+                }
+                if !matches!(attr.size, SizeAttribute::None | SizeAttribute::Remain) {
+                    return Err(syn::Error::new(
+                        attr.span,
+                        "ZSeq type only supports the remain size attribute",
+                    ));
+                }
+                Ok(())
+            }
             ZenohType::Option(inner_ty) => {
+                if matches!(**inner_ty, ZenohType::Seq) && s {
+                    return Err(syn::Error::new(
+                        attr.span,
+                        "Option<ZSeq> type does not support size attributes",
+                    ));
+                }
+
                 if d || h {
                     return Err(syn::Error::new(
                         attr.span,
@@ -136,6 +227,8 @@ impl ZenohType {
                     header: HeaderAttribute::None,
                     ext: ExtAttribute::None,
                     default: DefaultAttribute::None,
+                    encoding: EncodingAttribute::None,
+                    flags: None,
                     span: attr.span,
                 };
 
@@ -151,23 +244,55 @@ impl ZenohType {
                     if let syn::PathArguments::AngleBracketed(args) =
                         &type_path.path.segments[0].arguments
                         && args.args.len() == 1
-                            && let syn::GenericArgument::Type(inner_ty) = &args.args[0] {
-                                let zenoh_type = ZenohType::from_type(inner_ty)?;
-                                return Ok(ZenohType::Option(Box::new(zenoh_type)));
-                            }
+                        && let syn::GenericArgument::Type(inner_ty) = &args.args[0]
+                    {
+                        let zenoh_type = ZenohType::from_type(inner_ty)?;
+                        return Ok(ZenohType::Option(Box::new(zenoh_type)));
+                    }
                     return Err(syn::Error::new_spanned(
                         ty,
                         "Option must have exactly one type argument",
                     ));
                 }
 
-                let ident = &type_path.path.segments.last().unwrap().ident;
-                match ident.to_string().as_str() {
+                if is_bytes(&type_path.path) {
+                    return Ok(ZenohType::ByteSlice);
+                }
+
+                let segment = type_path.path.segments.last().unwrap();
+                match segment.ident.to_string().as_str() {
                     "u8" => Ok(ZenohType::U8),
                     "u16" => Ok(ZenohType::U16),
                     "u32" => Ok(ZenohType::U32),
                     "u64" => Ok(ZenohType::U64),
                     "usize" => Ok(ZenohType::USize),
+                    "i8" => Ok(ZenohType::I8),
+                    "i16" => Ok(ZenohType::I16),
+                    "i32" => Ok(ZenohType::I32),
+                    "i64" => Ok(ZenohType::I64),
+                    "isize" => Ok(ZenohType::ISize),
+                    "bool" => Ok(ZenohType::Bool),
+                    "f32" => Ok(ZenohType::F32),
+                    "f64" => Ok(ZenohType::F64),
+                    "String" => Ok(ZenohType::Str),
+                    "ZSeq" => Ok(ZenohType::Seq),
+                    "Vec" => match type_arguments(segment).next() {
+                        Some(elem) if is_u8(elem) => Ok(ZenohType::ByteSlice),
+                        _ => Err(syn::Error::new_spanned(ty, "Unsupported Vec element type")),
+                    },
+                    "Cow" => match type_arguments(segment).next() {
+                        Some(Type::Path(type_path)) if type_path.path.is_ident("str") => {
+                            Ok(ZenohType::Str)
+                        }
+                        Some(Type::Slice(TypeSlice { elem, .. })) if is_u8(elem) => {
+                            Ok(ZenohType::ByteSlice)
+                        }
+                        _ => Err(syn::Error::new_spanned(
+                            ty,
+                            "Unsupported Cow type, expected Cow<'_, str> or Cow<'_, [u8]>",
+                        )),
+                    },
+                    // `Box<T>` delegates to `T` and behaves like any other struct field.
                     _ => Ok(ZenohType::ZStruct),
                 }
             }
@@ -220,3 +345,27 @@ impl ZenohType {
         }
     }
 }
+
+fn type_arguments(segment: &PathSegment) -> impl Iterator<Item = &Type> {
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => Some(&args.args),
+        _ => None,
+    };
+
+    args.into_iter().flatten().filter_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+fn is_u8(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.is_ident("u8"))
+}
+
+/// Whether `path` is `bytes::Bytes` (or `::bytes::Bytes`). Other types named `Bytes` are
+/// regular structs.
+fn is_bytes(path: &syn::Path) -> bool {
+    let segments = path.segments.iter().map(|segment| &segment.ident).collect::<Vec<_>>();
+
+    matches!(segments.as_slice(), [krate, ty] if *krate == "bytes" && *ty == "Bytes")
+}
@@ -0,0 +1,225 @@
+use proc_macro2::{Span, TokenStream};
+use syn::{DeriveInput, Lifetime, visit_mut::VisitMut};
+
+use crate::{
+    model::{ZenohField, ZenohStruct, attribute::DefaultAttribute},
+    r#struct::{decode, encode, header, len},
+};
+
+/// Replaces the struct lifetime by another one (`'static` or `'_`) in field types.
+struct ReplaceLifetime<'l>(&'l Lifetime, &'static str);
+
+impl VisitMut for ReplaceLifetime<'_> {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime == self.0 {
+            *lifetime = Lifetime::new(self.1, lifetime.span());
+        }
+    }
+}
+
+pub fn parse(input: &DeriveInput, r#struct: &ZenohStruct, ext: bool) -> syn::Result<TokenStream> {
+    let Some(owned) = &r#struct.owned else {
+        return Ok(quote::quote! {});
+    };
+
+    let ident = &r#struct.ident;
+    let generics = &r#struct.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    if generics.type_params().next().is_some() || generics.const_params().next().is_some() {
+        return Err(syn::Error::new_spanned(
+            owned,
+            "owned companions are only supported for structs without type or const parameters",
+        ));
+    }
+
+    let borrowed = generics.lifetimes().next().map(|param| &param.lifetime);
+    let lifetime = borrowed
+        .cloned()
+        .unwrap_or_else(|| Lifetime::new("'a", Span::call_site()));
+
+    if owned == "Self" {
+        if borrowed.is_some() {
+            return Err(syn::Error::new_spanned(
+                owned,
+                "owned = Self is only allowed on structs without lifetime",
+            ));
+        }
+
+        return Ok(quote::quote! {
+            impl<#lifetime> zenoh_codec::ZIntoOwned<#lifetime> for #ident {
+                type Owned = Self;
+
+                fn into_owned(self) -> Self {
+                    self
+                }
+
+                fn as_borrowed(owned: &#lifetime Self) -> Self {
+                    Clone::clone(owned)
+                }
+            }
+        });
+    }
+
+    let fields = match &input.data {
+        syn::Data::Struct(data) => &data.fields,
+        _ => unreachable!("Only structs are supported, this should have been caught earlier"),
+    };
+
+    let vis = &input.vis;
+    let names = fields.members().collect::<Vec<_>>();
+    let tys = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+
+    let declarations = fields.iter().map(|field| {
+        let vis = &field.vis;
+
+        let mut ty = field.ty.clone();
+        ReplaceLifetime(&lifetime, "'static").visit_type_mut(&mut ty);
+
+        match &field.ident {
+            Some(name) => quote::quote! {
+                #vis #name: <#ty as zenoh_codec::ZIntoOwned<'static>>::Owned
+            },
+            None => quote::quote! {
+                #vis <#ty as zenoh_codec::ZIntoOwned<'static>>::Owned
+            },
+        }
+    });
+
+    let body = match fields {
+        syn::Fields::Named(_) => quote::quote! { { #(#declarations,)* } },
+        syn::Fields::Unnamed(_) => quote::quote! { ( #(#declarations,)* ); },
+        syn::Fields::Unit => quote::quote! { ; },
+    };
+
+    let borrowed_ty = match borrowed {
+        Some(_) => quote::quote! { #ident<'_> },
+        None => quote::quote! { #ident },
+    };
+
+    let doc = format!(" Owned counterpart of [`{ident}`], see [`{ident}::into_owned`].");
+
+    let codec = companion(input, owned, &lifetime)?;
+    let companion_header = header::parse(&codec)?;
+    let companion_len = len::parse(&codec)?;
+    let companion_encode = encode::parse(&codec, false)?;
+    let companion_encode_vectored = encode::parse(&codec, true)?;
+    let companion_decode = decode::parse(&codec)?;
+
+    let zext = match ext {
+        true => quote::quote! {
+            impl<#lifetime> zenoh_codec::ZExt<#lifetime> for #owned {
+                const KIND: zenoh_codec::ZExtKind = <#ident #ty_generics as zenoh_codec::ZExt<#lifetime>>::KIND;
+            }
+        },
+        false => quote::quote! {},
+    };
+
+    Ok(quote::quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq)]
+        #vis struct #owned #body
+
+        impl<#lifetime> zenoh_codec::ZIntoOwned<#lifetime> for #ident #ty_generics #where_clause {
+            type Owned = #owned;
+
+            fn into_owned(self) -> #owned {
+                #owned {
+                    #(#names: zenoh_codec::ZIntoOwned::into_owned(self. #names),)*
+                }
+            }
+
+            fn as_borrowed(owned: &#lifetime #owned) -> Self {
+                Self {
+                    #(#names: <#tys as zenoh_codec::ZIntoOwned<#lifetime>>::as_borrowed(&owned. #names),)*
+                }
+            }
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            pub fn into_owned(self) -> #owned {
+                <Self as zenoh_codec::ZIntoOwned<'_>>::into_owned(self)
+            }
+        }
+
+        impl #owned {
+            pub fn as_borrowed(&self) -> #borrowed_ty {
+                <#borrowed_ty as zenoh_codec::ZIntoOwned<'_>>::as_borrowed(self)
+            }
+        }
+
+        #zext
+
+        #companion_header
+
+        impl zenoh_codec::ZStructEncode for #owned {
+            fn z_len(&self) -> usize {
+                #companion_len
+            }
+
+            fn z_encode<W: zenoh_codec::ZWriterExt>(&self, w: &mut W) -> zenoh_codec::ZResult<()> {
+                let result = (move || {
+                    #companion_encode
+
+                    Ok(())
+                })();
+
+                zenoh_codec::z_encode_sized(self, result)
+            }
+
+            fn z_encode_vectored<'s>(&'s self, w: &mut zenoh_codec::ZVectoredWriter<'s, '_>) -> zenoh_codec::ZResult<()> {
+                let result = (move || {
+                    #companion_encode_vectored
+
+                    Ok(())
+                })();
+
+                zenoh_codec::z_encode_sized(self, result)
+            }
+        }
+
+        impl<#lifetime> zenoh_codec::ZStructDecode<#lifetime> for #owned {
+            fn z_decode<R: zenoh_codec::ZReaderExt<#lifetime>>(r: &mut R) -> zenoh_codec::ZResult<Self> {
+                #companion_decode
+            }
+        }
+    })
+}
+
+/// Model of the companion, encoded and decoded field by field like the borrowed struct (owned
+/// fields share the wire format of their borrowed counterpart), so that encoding it does not
+/// clone them and decoding it copies owned fields out of the reader directly.
+fn companion(
+    input: &DeriveInput,
+    owned: &syn::Ident,
+    lifetime: &Lifetime,
+) -> syn::Result<ZenohStruct> {
+    let mut codec = ZenohStruct::from_derive_input(input)?;
+    codec.ident = owned.clone();
+    codec.generics = syn::Generics::default();
+
+    for field in &mut codec.fields {
+        let fields = match field {
+            ZenohField::Regular { field } => core::slice::from_mut(&mut **field),
+            ZenohField::ExtBlock { exts } => exts.as_mut_slice(),
+        };
+
+        for field in fields {
+            // Extension defaults are borrowed values, compared against borrowed views of the
+            // owned fields so that encoding does not allocate.
+            if let DefaultAttribute::Expr(expr) = &field.attr.default {
+                let mut ty = field.raw_ty.clone();
+                ReplaceLifetime(lifetime, "'_").visit_type_mut(&mut ty);
+
+                field.attr.default = DefaultAttribute::Owned {
+                    expr: expr.clone(),
+                    ty: Box::new(ty),
+                };
+            }
+
+            ReplaceLifetime(lifetime, "'static").visit_type_mut(&mut field.raw_ty);
+        }
+    }
+
+    Ok(codec)
+}
@@ -1,7 +1,10 @@
 use proc_macro2::TokenStream;
 use syn::DeriveInput;
 
-use crate::model::ZenohStruct;
+use crate::{
+    model::{ZenohStruct, generics},
+    owned,
+};
 
 pub mod header;
 
@@ -13,32 +16,63 @@ pub fn derive_zstruct(input: DeriveInput) -> syn::Result<TokenStream> {
     let r#struct = ZenohStruct::from_derive_input(&input)?;
     let ident = &r#struct.ident;
 
-    let generics = &r#struct.generics;
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = r#struct.generics.split_for_impl();
+
+    let encode_generics = generics::bounded(
+        r#struct.generics.clone(),
+        quote::quote! { zenoh_codec::ZStructEncode },
+    );
+    let (impl_generics, _, where_clause) = encode_generics.split_for_impl();
+
+    let lifetime = generics::lifetime(&r#struct.generics);
+    let decode_generics = generics::bounded(
+        generics::with_lifetime(&r#struct.generics, &lifetime),
+        quote::quote! { zenoh_codec::ZStructDecode<#lifetime> },
+    );
+    let (decode_impl_generics, _, decode_where_clause) = decode_generics.split_for_impl();
 
     let header = header::parse(&r#struct)?;
 
     let len = len::parse(&r#struct)?;
-    let encode = encode::parse(&r#struct)?;
+    let encode = encode::parse(&r#struct, false)?;
+    let encode_vectored = encode::parse(&r#struct, true)?;
     let decode = decode::parse(&r#struct)?;
 
+    let owned = owned::parse(&input, &r#struct, false)?;
+
     Ok(quote::quote! {
         #header
 
+        #owned
+
         impl #impl_generics zenoh_codec::ZStructEncode for #ident #ty_generics #where_clause {
             fn z_len(&self) -> usize {
                 #len
             }
 
-            fn z_encode(&self, w: &mut zenoh_codec::ZWriter) -> zenoh_codec::ZResult<()> {
-                #encode
+            fn z_encode<W: zenoh_codec::ZWriterExt>(&self, w: &mut W) -> zenoh_codec::ZResult<()> {
+                let result = (move || {
+                    #encode
+
+                    Ok(())
+                })();
+
+                zenoh_codec::z_encode_sized(self, result)
+            }
+
+            fn z_encode_vectored<'s>(&'s self, w: &mut zenoh_codec::ZVectoredWriter<'s, '_>) -> zenoh_codec::ZResult<()> {
+                let result = (move || {
+                    #encode_vectored
+
+                    Ok(())
+                })();
 
-                Ok(())
+                zenoh_codec::z_encode_sized(self, result)
             }
         }
 
-        impl<'a> zenoh_codec::ZStructDecode<'a> for #ident #ty_generics #where_clause {
-            fn z_decode(r: &mut zenoh_codec::ZReader<'a>) -> zenoh_codec::ZResult<Self> {
+        impl #decode_impl_generics zenoh_codec::ZStructDecode<#lifetime> for #ident #ty_generics #decode_where_clause {
+            fn z_decode<R: zenoh_codec::ZReaderExt<#lifetime>>(r: &mut R) -> zenoh_codec::ZResult<Self> {
                 #decode
             }
         }
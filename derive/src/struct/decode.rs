@@ -1,41 +1,108 @@
 use proc_macro2::TokenStream;
 
-use crate::model::{
-    ZenohField, ZenohStruct,
-    attribute::{
-        DefaultAttribute, ExtAttribute, HeaderAttribute, PresenceAttribute, SizeAttribute,
+use crate::{
+    model::{
+        ZenohField, ZenohStruct,
+        attribute::{
+            EncodingAttribute, ExtAttribute, HeaderAttribute, PresenceAttribute, SizeAttribute,
+        },
+        ty::ZenohType,
     },
-    ty::ZenohType,
+    r#struct::header,
 };
 
 pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
+    let name = r#struct.ident.to_string();
+
     let mut body = Vec::<TokenStream>::new();
     let mut declaration = Vec::<TokenStream>::new();
 
     if r#struct.header.is_some() {
-        body.push(quote::quote! {
+        let header = quote::quote! {
             let header: u8 = <u8 as zenoh_codec::ZStructDecode>::z_decode(r)?;
-        });
+
+            if !Self::matches_header(header) {
+                return Err(zenoh_codec::ZCodecError::HeaderMismatch);
+            }
+        };
+
+        body.push(traced(
+            r#struct,
+            &quote::quote! { header },
+            "header",
+            &[header],
+        ));
     }
 
     for field in &r#struct.fields {
         match field {
             ZenohField::Regular { field } => {
                 let access = &field.access;
+                let binding = &field.binding;
                 let ty = &field.ty;
                 let attr = &field.attr;
 
                 declaration.push(quote::quote! {
-                    #access
+                    #access: #binding
                 });
 
-                if let HeaderAttribute::Mask(mask) = &attr.header {
+                let mut stmts = Vec::<TokenStream>::new();
+
+                if let Some(flags) = &field.flags {
+                    let byte = &flags.byte;
+                    let driven = &flags.driven;
+
+                    stmts.push(quote::quote! {
+                        let #byte: u8 = <u8 as zenoh_codec::ZStructDecode>::z_decode(r)?;
+                    });
+                    body.push(traced(
+                        r#struct,
+                        &quote::quote! { #byte },
+                        &field_name(access),
+                        &stmts,
+                    ));
                     body.push(quote::quote! {
-                            let #access = {
-                                let v = header & #mask;
-                                <_ as TryFrom<u8>>::try_from(v >> #mask.trailing_zeros()).map_err(|_| zenoh_codec::ZCodecError::CouldNotParse)?
+                        let #binding: u8 = #byte & !(0u8 #(| #driven)*);
+                    });
+                    continue;
+                }
+
+                if let HeaderAttribute::Mask(mask) = &attr.header {
+                    let byte = &mask.byte;
+                    let v = match ty {
+                        ZenohType::Bool => quote::quote! {
+                            const {
+                                assert!(
+                                    #mask.count_ones() == 1,
+                                    "bool header fields require a single-bit slot"
+                                )
                             };
-                        });
+                            (#byte & #mask) != 0
+                        },
+                        ZenohType::I8 => quote::quote! {
+                            let v = (#byte & #mask) >> #mask.trailing_zeros();
+                            ((v >> 1) as i8) ^ -((v & 1) as i8)
+                        },
+                        ZenohType::ZStruct => {
+                            let check = header::slot_width_check(&field.raw_ty, mask);
+                            quote::quote! {
+                                #check
+                                let v = #byte & #mask;
+                                <_ as TryFrom<u8>>::try_from(v >> #mask.trailing_zeros()).map_err(|_| zenoh_codec::ZCodecError::IntegerOutOfRange)?
+                            }
+                        }
+                        _ => quote::quote! {
+                            let v = #byte & #mask;
+                            <_ as TryFrom<u8>>::try_from(v >> #mask.trailing_zeros()).map_err(|_| zenoh_codec::ZCodecError::IntegerOutOfRange)?
+                        },
+                    };
+
+                    stmts.push(quote::quote! {
+                        let #binding = {
+                            #v
+                        };
+                    });
+                    body.push(traced(r#struct, binding, &field_name(access), &stmts));
                     continue;
                 }
 
@@ -45,43 +112,91 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                     | ZenohType::U32
                     | ZenohType::U64
                     | ZenohType::USize
+                    | ZenohType::I8
+                    | ZenohType::I16
+                    | ZenohType::I32
+                    | ZenohType::I64
+                    | ZenohType::ISize
+                    | ZenohType::Bool
+                    | ZenohType::F32
+                    | ZenohType::F64
                     | ZenohType::ByteArray => {
-                        body.push(quote::quote! {
-                            let #access = < _ as zenoh_codec::ZStructDecode>::z_decode(r)?;
-                        });
+                        let from_bytes = match &attr.encoding {
+                            EncodingAttribute::Le => Some(quote::quote! { from_le_bytes }),
+                            EncodingAttribute::Be => Some(quote::quote! { from_be_bytes }),
+                            EncodingAttribute::None => None,
+                        };
+
+                        match (from_bytes, ty.int_type()) {
+                            // Copied out rather than borrowed, the bytes may straddle chunks.
+                            (Some(from_bytes), Some(int)) => stmts.push(quote::quote! {
+                                let mut #binding = [0u8; core::mem::size_of::<#int>()];
+                                if zenoh_codec::ZReaderExt::remaining(r) < #binding.len() {
+                                    return Err(zenoh_codec::ZCodecError::CouldNotRead);
+                                }
+                                zenoh_codec::ZReaderExt::read_into(r, &mut #binding)?;
+                                let #binding = <#int>:: #from_bytes(#binding);
+                            }),
+                            _ => stmts.push(quote::quote! {
+                                let #binding = < _ as zenoh_codec::ZStructDecode>::z_decode(r)?;
+                            }),
+                        }
                     }
                     ZenohType::ByteSlice | ZenohType::Str | ZenohType::ZStruct => {
                         match &attr.size {
                             SizeAttribute::Prefixed => {
-                                body.push(quote::quote! {
-                                        let #access = < usize as zenoh_codec::ZStructDecode>::z_decode(r)?;
-                                        let #access = < _ as zenoh_codec::ZStructDecode>::z_decode(&mut < zenoh_codec::ZReader as zenoh_codec::ZReaderExt>::sub(r, #access)?)?;
+                                stmts.push(quote::quote! {
+                                        let #binding = < usize as zenoh_codec::ZStructDecode>::z_decode(r)?;
+                                        let #binding = < _ as zenoh_codec::ZStructDecode>::z_decode_exact(&mut zenoh_codec::ZReaderExt::sub(r, #binding)?)?;
                                     });
                             }
                             SizeAttribute::Header(mask) => {
+                                let byte = &mask.byte;
                                 let e: u8 = !(attr.maybe_empty) as u8;
-                                body.push(quote::quote! {
-                                        let #access = (((header & #mask) >> #mask.trailing_zeros()) + #e) as usize;
-                                        let #access = < _ as zenoh_codec::ZStructDecode>::z_decode(&mut < zenoh_codec::ZReader as zenoh_codec::ZReaderExt>::sub(r, #access)?)?;
+                                stmts.push(quote::quote! {
+                                        let #binding = (((#byte & #mask) >> #mask.trailing_zeros()) + #e) as usize;
+                                        let #binding = < _ as zenoh_codec::ZStructDecode>::z_decode_exact(&mut zenoh_codec::ZReaderExt::sub(r, #binding)?)?;
                                     });
                             }
+                            SizeAttribute::Field(field) => {
+                                let len = &field.binding;
+                                stmts.push(quote::quote! {
+                                    let #binding = <usize as TryFrom<_>>::try_from(#len).map_err(|_| zenoh_codec::ZCodecError::IntegerOutOfRange)?;
+                                    let #binding = < _ as zenoh_codec::ZStructDecode>::z_decode_exact(&mut zenoh_codec::ZReaderExt::sub(r, #binding)?)?;
+                                });
+                            }
                             _ => {
-                                body.push(quote::quote! {
-                                    let #access = < _ as zenoh_codec::ZStructDecode>::z_decode(r)?;
+                                stmts.push(quote::quote! {
+                                    let #binding = < _ as zenoh_codec::ZStructDecode>::z_decode(r)?;
                                 });
                             }
                         }
                     }
+                    ZenohType::Seq => match &attr.size {
+                        SizeAttribute::Remain => stmts.push(quote::quote! {
+                            let #binding = zenoh_codec::ZSeq::z_decode_remain(r)?;
+                        }),
+                        _ => stmts.push(quote::quote! {
+                            let #binding = < _ as zenoh_codec::ZStructDecode>::z_decode(r)?;
+                        }),
+                    },
                     ZenohType::Option(_) => {
                         match &attr.presence {
                             PresenceAttribute::Prefixed => {
-                                body.push(quote::quote! {
-                                    let #access: bool = <u8 as zenoh_codec::ZStructDecode>::z_decode(r)? != 0;
+                                stmts.push(quote::quote! {
+                                    let #binding: bool = <u8 as zenoh_codec::ZStructDecode>::z_decode(r)? != 0;
                                 });
                             }
                             PresenceAttribute::Header(mask) => {
-                                body.push(quote::quote! {
-                                    let #access: bool = (header & #mask) != 0;
+                                let byte = &mask.byte;
+                                stmts.push(quote::quote! {
+                                    let #binding: bool = (#byte & #mask) != 0;
+                                });
+                            }
+                            PresenceAttribute::Field(field) => {
+                                let present = &field.binding;
+                                stmts.push(quote::quote! {
+                                    let #binding: bool = #present != Default::default();
                                 });
                             }
                             _ => unreachable!(
@@ -91,30 +206,42 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
 
                         match &attr.size {
                             SizeAttribute::Prefixed => {
-                                body.push(quote::quote! {
-                                    let #access = if #access {
-                                        let #access = < usize as zenoh_codec::ZStructDecode>::z_decode(r)?;
-                                        Some(< _ as zenoh_codec::ZStructDecode>::z_decode(&mut < zenoh_codec::ZReader as zenoh_codec::ZReaderExt>::sub(r, #access)?)?)
+                                stmts.push(quote::quote! {
+                                    let #binding = if #binding {
+                                        let #binding = < usize as zenoh_codec::ZStructDecode>::z_decode(r)?;
+                                        Some(< _ as zenoh_codec::ZStructDecode>::z_decode_exact(&mut zenoh_codec::ZReaderExt::sub(r, #binding)?)?)
                                     } else {
                                         None
                                     };
                                 });
                             }
                             SizeAttribute::Header(mask) => {
+                                let byte = &mask.byte;
                                 let e: u8 = !(attr.maybe_empty) as u8;
 
-                                body.push(quote::quote! {
-                                    let #access = if #access {
-                                            let #access = (((header & #mask) >> #mask.trailing_zeros()) + #e) as usize;
-                                        Some(< _ as zenoh_codec::ZStructDecode>::z_decode(&mut < zenoh_codec::ZReader as zenoh_codec::ZReaderExt>::sub(r, #access)?)?)
+                                stmts.push(quote::quote! {
+                                    let #binding = if #binding {
+                                            let #binding = (((#byte & #mask) >> #mask.trailing_zeros()) + #e) as usize;
+                                        Some(< _ as zenoh_codec::ZStructDecode>::z_decode_exact(&mut zenoh_codec::ZReaderExt::sub(r, #binding)?)?)
+                                    } else {
+                                        None
+                                    };
+                                });
+                            }
+                            SizeAttribute::Field(field) => {
+                                let len = &field.binding;
+                                stmts.push(quote::quote! {
+                                    let #binding = if #binding {
+                                        let #binding = <usize as TryFrom<_>>::try_from(#len).map_err(|_| zenoh_codec::ZCodecError::IntegerOutOfRange)?;
+                                        Some(< _ as zenoh_codec::ZStructDecode>::z_decode_exact(&mut zenoh_codec::ZReaderExt::sub(r, #binding)?)?)
                                     } else {
                                         None
                                     };
                                 });
                             }
                             _ => {
-                                body.push(quote::quote! {
-                                    let #access = if #access {
+                                stmts.push(quote::quote! {
+                                    let #binding = if #binding {
                                         Some(< _ as zenoh_codec::ZStructDecode>::z_decode(r)?)
                                     } else {
                                         None
@@ -124,6 +251,8 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                         }
                     }
                 }
+
+                body.push(traced(r#struct, binding, &field_name(access), &stmts));
             }
             ZenohField::ExtBlock { exts } => {
                 body.push(quote::quote! {
@@ -134,13 +263,16 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
 
                 for field in exts {
                     let access = &field.access;
+                    let binding = &field.binding;
                     let ty = &field.ty;
                     let attr = &field.attr;
 
                     declaration.push(quote::quote! {
-                        #access
+                        #access: #binding
                     });
 
+                    let field = field_name(access);
+
                     let id = match &attr.ext {
                         ExtAttribute::Expr(id) => id,
                         _ => unreachable!(
@@ -150,31 +282,33 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
 
                     match ty {
                         ZenohType::ZStruct => {
-                            let expr = match &attr.default {
-                                DefaultAttribute::Expr(expr) => expr,
-                                _ => unreachable!(
+                            let expr = match attr.default.value() {
+                                Some(expr) => expr,
+                                None => unreachable!(
                                     "ExtBlock fields ZStruct must have a default attribute, this should have been caught earlier"
                                 ),
                             };
 
                             body.push(quote::quote! {
-                                let mut #access = #expr;
+                                let mut #binding = #expr;
                             });
 
                             ext_body.push(quote::quote! {
                                 #id => {
-                                    #access = zenoh_codec::zext_decode::< _ >(r)?;
+                                    #binding = zenoh_codec::zext_decode::<_, _>(r)
+                                        .map_err(|e| zenoh_codec::ZReaderExt::trace(r, e, field_offset, #name, #field))?;
                                 }
                             });
                         }
                         ZenohType::Option(_) => {
                             body.push(quote::quote! {
-                                let mut #access: _ = None;
+                                let mut #binding: _ = None;
                             });
 
                             ext_body.push(quote::quote! {
                                 #id => {
-                                    #access = Some(zenoh_codec::zext_decode::< _ >(r)?);
+                                    #binding = Some(zenoh_codec::zext_decode::<_, _>(r)
+                                        .map_err(|e| zenoh_codec::ZReaderExt::trace(r, e, field_offset, #name, #field))?);
                                 }
                             });
                         }
@@ -184,19 +318,24 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                     }
                 }
 
+                // Errors that do not belong to a known extension are reported on the struct itself.
                 body.push(quote::quote! {
                     while has_ext {
-                        let (ext_id, ext_kind, mandatory, more) = zenoh_codec::decode_ext_header(r)?;
+                        let field_offset = zenoh_codec::ZReaderExt::offset(r);
+                        let (ext_id, ext_kind, mandatory, more) = zenoh_codec::decode_ext_header(r)
+                            .map_err(|e| zenoh_codec::ZReaderExt::trace(r, e, field_offset, #name, ""))?;
                         has_ext = more;
 
                         match ext_id {
                             #(#ext_body,)*
                             _ => {
                                 if mandatory {
-                                    return Err(zenoh_codec::ZCodecError::UnsupportedMandatoryExtension);
+                                    let e = zenoh_codec::ZCodecError::UnknownMandatoryExtension { id: ext_id };
+                                    return Err(zenoh_codec::ZReaderExt::trace(r, e, field_offset, #name, ""));
                                 }
 
-                                zenoh_codec::skip_ext(r, ext_kind)?;
+                                zenoh_codec::skip_ext(r, ext_kind)
+                                    .map_err(|e| zenoh_codec::ZReaderExt::trace(r, e, field_offset, #name, ""))?;
                             }
                         }
                     }
@@ -211,3 +350,29 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
         Ok(Self { #(#declaration),* })
     })
 }
+
+/// Wraps the statements decoding `binding` so that, on failure, the reader is told which field
+/// of which struct failed and at which offset it started.
+pub fn traced(
+    r#struct: &ZenohStruct,
+    binding: &TokenStream,
+    field: &str,
+    stmts: &[TokenStream],
+) -> TokenStream {
+    let name = r#struct.ident.to_string();
+
+    quote::quote! {
+        let #binding = {
+            let field_offset = zenoh_codec::ZReaderExt::offset(r);
+
+            match (|| -> zenoh_codec::ZResult<_> { #(#stmts)* Ok(#binding) })() {
+                Ok(v) => v,
+                Err(e) => return Err(zenoh_codec::ZReaderExt::trace(r, e, field_offset, #name, #field)),
+            }
+        };
+    }
+}
+
+pub fn field_name(access: &TokenStream) -> String {
+    access.to_string().trim_start_matches("r#").to_owned()
+}
@@ -1,14 +1,25 @@
 use proc_macro2::TokenStream;
 
-use crate::model::{
-    ZenohField, ZenohStruct,
-    attribute::{
-        DefaultAttribute, ExtAttribute, HeaderAttribute, PresenceAttribute, SizeAttribute,
+use crate::{
+    model::{
+        ZenohField, ZenohStruct,
+        attribute::{
+            EncodingAttribute, ExtAttribute, HeaderAttribute, HeaderSlot, PresenceAttribute,
+            SizeAttribute,
+        },
+        ty::ZenohType,
     },
-    ty::ZenohType,
+    r#struct::header,
 };
 
-pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
+/// Generates the body of `z_encode`, or of `z_encode_vectored` when `vectored` is set, in which
+/// case fields are encoded with their vectored flavour so payloads can be borrowed.
+pub fn parse(r#struct: &ZenohStruct, vectored: bool) -> syn::Result<TokenStream> {
+    let z_encode = match vectored {
+        true => quote::quote! { z_encode_vectored },
+        false => quote::quote! { z_encode },
+    };
+
     let mut body = Vec::<TokenStream>::new();
     let mut header = Vec::<TokenStream>::new();
 
@@ -25,9 +36,55 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                 let ty = &field.ty;
                 let attr = &field.attr;
 
+                // Fields referenced by `size = field(..)` or `presence = field(..)` are filled.
+                let value = match &field.fill {
+                    Some(fill) => fill.value(ty, &field.raw_ty),
+                    None => quote::quote! { self. #access },
+                };
+
+                // The flags byte starts from the bits it keeps, later fields fill their slots.
+                if let Some(flags) = &field.flags {
+                    let byte = &flags.byte;
+                    let driven = &flags.driven;
+
+                    header.push(quote::quote! {
+                        let mut #byte: u8 = self. #access & !(0u8 #(| #driven)*);
+                    });
+                    body.push(quote::quote! {
+                        <u8 as zenoh_codec::ZStructEncode>::z_encode(&#byte, w)?;
+                    });
+                    continue;
+                }
+
                 if let HeaderAttribute::Mask(mask) = &attr.header {
-                    header.push(quote::quote! { header  |= {
-                        let v: u8 = self. #access.into();
+                    let byte = &mask.byte;
+
+                    // Signed values are zig-zag encoded, as in a VLE. Values that do not fit their
+                    // slot are rejected rather than truncated.
+                    let v = match ty {
+                        ZenohType::I8 => quote::quote! {
+                            let v: u8 = ((self. #access << 1) ^ (self. #access >> 7)) as u8;
+                            if v > #mask >> #mask .trailing_zeros() {
+                                return Err(zenoh_codec::ZCodecError::IntegerOutOfRange);
+                            }
+                        },
+                        ZenohType::ZStruct => {
+                            let check = header::slot_width_check(&field.raw_ty, mask);
+                            quote::quote! {
+                                #check
+                                let v: u8 = self. #access.into();
+                            }
+                        }
+                        _ => quote::quote! {
+                            let v: u8 = (#value).into();
+                            if v > #mask >> #mask .trailing_zeros() {
+                                return Err(zenoh_codec::ZCodecError::IntegerOutOfRange);
+                            }
+                        },
+                    };
+
+                    header.push(quote::quote! { #byte |= {
+                        #v
                         (v << (#mask .trailing_zeros())) & #mask
                     }; });
                     continue;
@@ -41,34 +98,79 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                     | ZenohType::U32
                     | ZenohType::U64
                     | ZenohType::USize
+                    | ZenohType::I8
+                    | ZenohType::I16
+                    | ZenohType::I32
+                    | ZenohType::I64
+                    | ZenohType::ISize
+                    | ZenohType::Bool
+                    | ZenohType::F32
+                    | ZenohType::F64
                     | ZenohType::ByteArray
                     | ZenohType::ByteSlice
                     | ZenohType::Str
                     | ZenohType::ZStruct => {
+                        let to_bytes = match &attr.encoding {
+                            EncodingAttribute::Le => Some(quote::quote! { to_le_bytes }),
+                            EncodingAttribute::Be => Some(quote::quote! { to_be_bytes }),
+                            EncodingAttribute::None => None,
+                        };
+
+                        if let Some(to_bytes) = to_bytes {
+                            body.push(quote::quote! {
+                                zenoh_codec::ZWriterExt::write_exact(w, &(#value). #to_bytes())?;
+                            });
+                            continue;
+                        }
+
+                        // A filled value is a temporary, it cannot be borrowed by a vectored writer.
+                        if field.fill.is_some() {
+                            body.push(quote::quote! {
+                                < _ as zenoh_codec::ZStructEncode>::z_encode(&(#value), w)?;
+                            });
+                            continue;
+                        }
+
                         match &attr.size {
                             SizeAttribute::Prefixed => {
                                 body.push(quote::quote! {
-                                    <usize as zenoh_codec::ZStructEncode>::z_encode(&< _ as zenoh_codec::ZStructEncode>::z_len(&self. #access), w)?;
+                                    zenoh_codec::ZWriterExt::write_prefixed(w, |w| < _ as zenoh_codec::ZStructEncode>:: #z_encode(&self. #access, w))?;
                                 });
+                                continue;
                             }
                             SizeAttribute::Header(mask) => {
-                                let e: u8 = (!attr.maybe_empty) as u8;
+                                let byte = &mask.byte;
+                                let slot = header_len(
+                                    mask,
+                                    attr.maybe_empty,
+                                    quote::quote! { &self. #access },
+                                );
                                 header.push(quote::quote! {
-                                    header |= {
-                                        let shift = #mask .trailing_zeros();
-                                        let len = < _ as zenoh_codec::ZStructEncode>::z_len(&self. #access) as u8;
-
-                                        ((len - #e) << shift) & #mask
-                                    };
+                                    #byte |= #slot;
                                 });
                             }
                             _ => {}
                         }
 
                         body.push(quote::quote! {
-                            < _ as zenoh_codec::ZStructEncode>::z_encode(&self. #access, w)?;
+                            < _ as zenoh_codec::ZStructEncode>:: #z_encode(&self. #access, w)?;
                         });
                     }
+                    ZenohType::Seq => match &attr.size {
+                        SizeAttribute::Remain => {
+                            let z_encode_remain = match vectored {
+                                true => quote::quote! { z_encode_remain_vectored },
+                                false => quote::quote! { z_encode_remain },
+                            };
+
+                            body.push(quote::quote! {
+                                zenoh_codec::ZSeq:: #z_encode_remain(&self. #access, w)?;
+                            });
+                        }
+                        _ => body.push(quote::quote! {
+                            < _ as zenoh_codec::ZStructEncode>:: #z_encode(&self. #access, w)?;
+                        }),
+                    },
                     ZenohType::Option(_) => {
                         match &attr.presence {
                             PresenceAttribute::Prefixed => {
@@ -77,9 +179,10 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                                 });
                             }
                             PresenceAttribute::Header(mask) => {
+                                let byte = &mask.byte;
                                 header.push(quote::quote! {
                                     if self. #access .is_some() {
-                                        header |= #mask ;
+                                        #byte |= #mask ;
                                     }
                                 });
                             }
@@ -90,20 +193,18 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                             SizeAttribute::Prefixed => {
                                 body.push(quote::quote! {
                                     if let Some(inner) = &self. #access {
-                                        <usize as zenoh_codec::ZStructEncode>::z_encode(&< _ as zenoh_codec::ZStructEncode>::z_len(inner), w)?;
+                                        zenoh_codec::ZWriterExt::write_prefixed(w, |w| < _ as zenoh_codec::ZStructEncode>:: #z_encode(inner, w))?;
                                     }
                                 });
+                                continue;
                             }
                             SizeAttribute::Header(mask) => {
-                                let e: u8 = (!attr.maybe_empty) as u8;
+                                let byte = &mask.byte;
+                                let slot =
+                                    header_len(mask, attr.maybe_empty, quote::quote! { inner });
                                 header.push(quote::quote! {
                                     if let Some(inner) = &self. #access {
-                                        header |= {
-                                            let shift = #mask .trailing_zeros();
-                                            let len = < _ as zenoh_codec::ZStructEncode>::z_len(inner) as u8;
-
-                                            ((len - #e) << shift) & #mask
-                                        };
+                                        #byte |= #slot;
                                     }
                                 });
                             }
@@ -112,7 +213,7 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
 
                         body.push(quote::quote! {
                             if let Some(inner) = &self. #access {
-                                < _ as zenoh_codec::ZStructEncode>::z_encode(inner, w)?;
+                                < _ as zenoh_codec::ZStructEncode>:: #z_encode(inner, w)?;
                             }
                         });
                     }
@@ -142,25 +243,37 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                         false => quote::quote! { false },
                     };
 
+                    let zext_encode = match vectored {
+                        true => {
+                            quote::quote! { zenoh_codec::zext_encode_vectored::<_, #id, #mandatory> }
+                        }
+                        false => {
+                            quote::quote! { zenoh_codec::zext_encode::<_, _, #id, #mandatory> }
+                        }
+                    };
+
                     match ty {
                         ZenohType::ZStruct => {
-                            let expr = match &attr.default {
-                                DefaultAttribute::Expr(expr) => expr,
-                                _ => unreachable!(
+                            let differs = match attr
+                                .default
+                                .differs(quote::quote! { self. #access })
+                            {
+                                Some(differs) => differs,
+                                None => unreachable!(
                                     "ExtBlock fields ZStruct must have a default attribute, this should have been caught earlier"
                                 ),
                             };
 
                             header.push(quote::quote! {
-                                if &self. #access  != &#expr {
+                                if #differs {
                                     n_exts += 1;
                                 }
                             });
 
                             enc_ext.push(quote::quote! {
-                                if &self. #access  != &#expr {
+                                if #differs {
                                     n_exts -= 1;
-                                    zenoh_codec::zext_encode::<_, #id, #mandatory>(&self. #access, w, n_exts != 0)?;
+                                    #zext_encode(&self. #access, w, n_exts != 0)?;
                                 }
                             });
                         }
@@ -174,7 +287,7 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                             enc_ext.push(quote::quote! {
                                 if let Some(inner) = &self. #access {
                                     n_exts -= 1;
-                                    zenoh_codec::zext_encode::<_, #id, #mandatory>(inner, w, n_exts != 0)?;
+                                    #zext_encode(inner, w, n_exts != 0)?;
                                 }
                             });
                         }
@@ -212,3 +325,20 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
         #(#body)*
     })
 }
+
+/// Length of `value` stored in a header slot, minus one unless it may be empty. Encoding fails
+/// with `IntegerOutOfRange` if it does not fit in the slot, or if an empty value cannot be
+/// represented.
+fn header_len(mask: &HeaderSlot, maybe_empty: bool, value: TokenStream) -> TokenStream {
+    let e = (!maybe_empty) as usize;
+
+    quote::quote! {{
+        let shift = #mask .trailing_zeros();
+        let len = < _ as zenoh_codec::ZStructEncode>::z_len(#value)
+            .checked_sub(#e)
+            .filter(|len| *len <= (#mask >> shift) as usize)
+            .ok_or(zenoh_codec::ZCodecError::IntegerOutOfRange)?;
+
+        ((len as u8) << shift) & #mask
+    }}
+}
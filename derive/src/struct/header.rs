@@ -1,55 +1,51 @@
 use proc_macro2::{Span, TokenStream};
-use syn::Ident;
+use syn::{Ident, LitStr};
 
-use crate::model::ZenohStruct;
+use crate::model::{ZenohField, ZenohStruct, attribute::HeaderSlot};
 
 pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
-    if let Some(header) = &r#struct.header {
-        let ident = &r#struct.ident;
-        let generics = &r#struct.generics;
-        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-
-        let mut shift = 8u8;
-        let content = header.expr.value();
-        let mut const_defs = Vec::new();
-        let mut base_header = Vec::new();
-
-        for part in content.split('|') {
-            let part = part.trim();
-            if part.is_empty() {
-                continue;
-            }
-            const_defs.push(parse_part(
-                part,
-                &mut shift,
-                &mut base_header,
-                header.expr.span(),
-            )?);
+    let ident = &r#struct.ident;
+    let generics = &r#struct.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut flags_defs = Vec::new();
+    for field in &r#struct.fields {
+        if let ZenohField::Regular { field } = field
+            && let Some(flags) = &field.flags
+        {
+            flags_defs.extend(parse_flags(&flags.expr)?);
         }
+    }
 
-        let base_header = if base_header.is_empty() {
-            quote::quote! { 0u8 }
-        } else {
-            base_header
-                .into_iter()
-                .reduce(|acc, expr| {
-                    quote::quote! { (#acc) | (#expr) }
-                })
-                .unwrap()
-        };
+    if let Some(header) = &r#struct.header {
+        let (const_defs, base_header, fixed_mask) = parse_layout(&header.expr, "Header")?;
 
-        if shift != 0 {
-            return Err(syn::Error::new(
-                header.expr.span(),
-                "Header declaration does not use all 8 bits",
-            ));
-        }
+        let base_header = or_all(base_header);
+        let fixed_mask = or_all(fixed_mask);
 
         Ok(quote::quote! {
             impl #impl_generics #ident #ty_generics #where_clause {
-                const HEADER_BASE: u8 = #base_header;
+                /// Fixed values of the header, the other bits being zero.
+                pub const HEADER_BASE: u8 = #base_header;
+
+                /// Bits of the header that hold fixed values, such as the message ID.
+                pub const HEADER_FIXED_MASK: u8 = #fixed_mask;
+
+                /// Whether `header` carries the fixed values of this message, i.e. whether it is
+                /// the header of this message.
+                pub const fn matches_header(header: u8) -> bool {
+                    (header & Self::HEADER_FIXED_MASK) == Self::HEADER_BASE
+                }
 
                 #(#const_defs)*
+
+                #(#flags_defs)*
+            }
+        })
+    } else if !flags_defs.is_empty() {
+        Ok(quote::quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #(#flags_defs)*
             }
         })
     } else {
@@ -57,10 +53,82 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
     }
 }
 
+/// Declares the slots of a flags byte. Unlike the header, it holds no fixed values and no `Z`.
+fn parse_flags(expr: &LitStr) -> syn::Result<Vec<TokenStream>> {
+    for part in expr.value().split('|').map(str::trim) {
+        if part.contains('=') || part == "Z" {
+            return Err(syn::Error::new(
+                expr.span(),
+                "Fixed values and the 'Z' placeholder are only allowed in the header declaration",
+            ));
+        }
+    }
+
+    parse_layout(expr, "Flags").map(|(const_defs, _, _)| const_defs)
+}
+
+type Layout = (Vec<TokenStream>, Vec<TokenStream>, Vec<TokenStream>);
+
+/// Parses an 8-bit layout into its slot constants, fixed values and fixed mask.
+fn parse_layout(expr: &LitStr, what: &str) -> syn::Result<Layout> {
+    let mut shift = 8u8;
+    let content = expr.value();
+    let mut const_defs = Vec::new();
+    let mut base_header = Vec::new();
+    let mut fixed_mask = Vec::new();
+
+    for part in content.split('|') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        const_defs.push(parse_part(
+            part,
+            &mut shift,
+            &mut base_header,
+            &mut fixed_mask,
+            expr.span(),
+        )?);
+    }
+
+    if shift != 0 {
+        return Err(syn::Error::new(
+            expr.span(),
+            format!("{what} declaration does not use all 8 bits"),
+        ));
+    }
+
+    Ok((const_defs, base_header, fixed_mask))
+}
+
+/// Checks at compile time that the values of a header field of type `ty` fit in its slot, for
+/// types that declare how many bits they need (see `ZHeaderBits`).
+pub fn slot_width_check(ty: &syn::Type, mask: &HeaderSlot) -> TokenStream {
+    quote::quote! {
+        const {
+            use zenoh_codec::ZHeaderBits as _;
+            assert!(
+                <#ty>::Z_BITS <= #mask.count_ones(),
+                "header slot too narrow for the values of this field"
+            )
+        };
+    }
+}
+
+fn or_all(parts: Vec<TokenStream>) -> TokenStream {
+    parts
+        .into_iter()
+        .reduce(|acc, expr| {
+            quote::quote! { (#acc) | (#expr) }
+        })
+        .unwrap_or_else(|| quote::quote! { 0u8 })
+}
+
 fn parse_part(
     part: &str,
     shift: &mut u8,
     base_header: &mut Vec<TokenStream>,
+    fixed_mask: &mut Vec<TokenStream>,
     span: Span,
 ) -> syn::Result<TokenStream> {
     if part == "_" {
@@ -107,20 +175,31 @@ fn parse_part(
                 })?
             };
 
+            *shift = shift.checked_sub(size).ok_or_else(|| {
+                syn::Error::new(span, "Not enough bits left in header declaration")
+            })?;
+
+            if u16::from(value) >= 1 << size {
+                return Err(syn::Error::new(
+                    span,
+                    format!(
+                        "Value '{}' does not fit in the {} bits of '{}' in header declaration",
+                        value_str, size, name_str
+                    ),
+                ));
+            }
+
             let x = syn::LitInt::new(&format!("0b{:b}", (1 << size) - 1), Span::call_site());
-            let y = *shift - size;
+            let y = *shift;
 
             let slot = quote::quote! { #x << #y };
             let shifted_value = quote::quote! { #value << #y };
             let value = quote::quote! { #value };
 
-            *shift = shift.checked_sub(size).ok_or_else(|| {
-                syn::Error::new(span, "Not enough bits left in header declaration")
-            })?;
-
             base_header.push(shifted_value);
+            fixed_mask.push(slot.clone());
 
-            if name.to_string() == "_" {
+            if name == "_" {
                 return Ok(quote::quote! {});
             }
 
@@ -130,14 +209,14 @@ fn parse_part(
                 const #name_slot: u8 = #slot;
             })
         } else {
-            let x = syn::LitInt::new(&format!("0b{:b}", (1 << size) - 1), Span::call_site());
-            let y = *shift - size;
-
             *shift = shift.checked_sub(size).ok_or_else(|| {
                 syn::Error::new(span, "Not enough bits left in header declaration")
             })?;
 
-            if name.to_string() == "_" {
+            let x = syn::LitInt::new(&format!("0b{:b}", (1 << size) - 1), Span::call_site());
+            let y = *shift;
+
+            if name == "_" {
                 return Ok(quote::quote! {});
             }
 
@@ -2,7 +2,7 @@ use proc_macro2::TokenStream;
 
 use crate::model::{
     ZenohField, ZenohStruct,
-    attribute::{DefaultAttribute, HeaderAttribute, PresenceAttribute, SizeAttribute},
+    attribute::{EncodingAttribute, HeaderAttribute, PresenceAttribute, SizeAttribute},
     ty::ZenohType,
 };
 
@@ -30,7 +30,33 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                     | ZenohType::U32
                     | ZenohType::U64
                     | ZenohType::USize
+                    | ZenohType::I8
+                    | ZenohType::I16
+                    | ZenohType::I32
+                    | ZenohType::I64
+                    | ZenohType::ISize
+                    | ZenohType::Bool
+                    | ZenohType::F32
+                    | ZenohType::F64
                     | ZenohType::ByteArray => {
+                        if !matches!(attr.encoding, EncodingAttribute::None) {
+                            len_parts.push(quote::quote! {
+                                core::mem::size_of_val(&self. #access)
+                            });
+                            continue;
+                        }
+
+                        // A filled VLE is sized by the value it will hold, not by the stored one.
+                        if let Some(fill) = &field.fill
+                            && !matches!(ty, ZenohType::U8 | ZenohType::Bool)
+                        {
+                            let raw = fill.raw();
+                            len_parts.push(quote::quote! {
+                                <u64 as zenoh_codec::ZStructEncode>::z_len(&((#raw) as u64))
+                            });
+                            continue;
+                        }
+
                         len_parts.push(quote::quote! {
                             < _ as zenoh_codec::ZStructEncode>::z_len(&self. #access)
                         });
@@ -38,14 +64,26 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                     ZenohType::ByteSlice | ZenohType::Str | ZenohType::ZStruct => {
                         if matches!(attr.size, SizeAttribute::Prefixed) {
                             len_parts.push(quote::quote! {
-                                <usize as zenoh_codec::ZStructEncode>::z_len(&< _ as zenoh_codec::ZStructEncode>::z_len(&self. #access))
+                                {
+                                    let len = < _ as zenoh_codec::ZStructEncode>::z_len(&self. #access);
+                                    <usize as zenoh_codec::ZStructEncode>::z_len(&len) + len
+                                }
                             });
+                            continue;
                         }
 
                         len_parts.push(quote::quote! {
                             < _ as zenoh_codec::ZStructEncode>::z_len(&self. #access)
                         });
                     }
+                    ZenohType::Seq => match &attr.size {
+                        SizeAttribute::Remain => len_parts.push(quote::quote! {
+                            zenoh_codec::ZSeq::z_len_remain(&self. #access)
+                        }),
+                        _ => len_parts.push(quote::quote! {
+                            < _ as zenoh_codec::ZStructEncode>::z_len(&self. #access)
+                        }),
+                    },
                     ZenohType::Option(_) => {
                         if matches!(attr.presence, PresenceAttribute::Prefixed) {
                             len_parts.push(quote::quote! { 1usize });
@@ -54,11 +92,13 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                         if matches!(attr.size, SizeAttribute::Prefixed) {
                             len_parts.push(quote::quote! {
                                 if let Some(inner) = &self. #access {
-                                    <usize as zenoh_codec::ZStructEncode>::z_len(&< _ as zenoh_codec::ZStructEncode>::z_len(inner))
+                                    let len = < _ as zenoh_codec::ZStructEncode>::z_len(inner);
+                                    <usize as zenoh_codec::ZStructEncode>::z_len(&len) + len
                                 } else {
                                     0usize
                                 }
                             });
+                            continue;
                         }
 
                         len_parts.push(quote::quote! {
@@ -78,20 +118,20 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
                     let attr = &field.attr;
 
                     match ty {
-                        ZenohType::ZStruct => match &attr.default {
-                            DefaultAttribute::Expr(expr) => {
-                                len_parts.push(quote::quote! {
-                                    if &self. #access  != &#expr {
+                        ZenohType::ZStruct => {
+                            match attr.default.differs(quote::quote! { self. #access }) {
+                                Some(differs) => len_parts.push(quote::quote! {
+                                    if #differs {
                                         zenoh_codec::zext_len::<_>(&self. #access)
                                     } else {
                                         0usize
                                     }
-                                });
+                                }),
+                                None => len_parts.push(quote::quote! {
+                                    zenoh_codec::zext_len::<_>(&self. #access)
+                                }),
                             }
-                            _ => len_parts.push(quote::quote! {
-                                zenoh_codec::zext_len::<_>(&self. #access)
-                            }),
-                        },
+                        }
                         ZenohType::Option(_) => {
                             len_parts.push(quote::quote! {
                                 if let Some(inner) = &self. #access {
@@ -119,7 +159,8 @@ pub fn parse(r#struct: &ZenohStruct) -> syn::Result<TokenStream> {
         .reduce(|acc, expr| quote::quote! { #acc + #expr })
         .unwrap();
 
+    // Parenthesized so that a leading block or `if` is not parsed as a statement.
     Ok(quote::quote! {
-        #len_body
+        (#len_body)
     })
 }
@@ -72,7 +72,7 @@ fn main() {
     };
 
     let mut data = [0u8; 128];
-    let mut writer = &mut data.as_mut_slice();
+    let mut writer = data.as_mut_slice();
 
     let len = <_ as ZStructEncode>::z_len(&x);
     <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
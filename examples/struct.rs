@@ -1,6 +1,6 @@
-use zenoh_codec::{ZReaderExt, ZStruct, ZStructDecode, ZStructEncode};
+use zenoh_codec::{ZEnum, ZReaderExt, ZStruct, ZStructDecode, ZStructEncode};
 
-// Only no-lifetime or single-lifetime structs are supported.
+// Structs may declare any lifetimes and type parameters: decoding borrows from the first lifetime.
 //
 // **Note**: this struct uses the `size = remain` flavour. This means that when decoding this struct, the
 // reader should be bounded to the exact size of the struct, otherwise decoding will fail. This is automatically done
@@ -54,7 +54,10 @@ struct ZStruct2<'a> {
     pub field2: Option<ZStruct1<'a>>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+// A fieldless enum stored in a header slot: the derive generates the conversions from and to
+// `u8`, and checks at compile time that every discriminant fits in the slot.
+#[derive(ZEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
 pub enum InterestMode {
     Final,
     Current,
@@ -62,29 +65,6 @@ pub enum InterestMode {
     CurrentFuture,
 }
 
-impl From<u8> for InterestMode {
-    fn from(value: u8) -> Self {
-        match value {
-            0 => InterestMode::Final,
-            1 => InterestMode::Current,
-            2 => InterestMode::Future,
-            3 => InterestMode::CurrentFuture,
-            _ => InterestMode::Final,
-        }
-    }
-}
-
-impl From<InterestMode> for u8 {
-    fn from(value: InterestMode) -> Self {
-        match value {
-            InterestMode::Final => 0,
-            InterestMode::Current => 1,
-            InterestMode::Future => 2,
-            InterestMode::CurrentFuture => 3,
-        }
-    }
-}
-
 // A struct with a header with assigned fixed bits and two u8 fields
 //  stored in the header.
 #[derive(ZStruct, PartialEq, Debug)]
@@ -113,7 +93,7 @@ fn main() {
     };
 
     let mut data = [0u8; 128];
-    let mut writer = &mut data.as_mut_slice();
+    let mut writer = data.as_mut_slice();
 
     let len = <_ as ZStructEncode>::z_len(&struct1);
     <_ as ZStructEncode>::z_encode(&struct1, &mut writer).unwrap();
@@ -139,7 +119,7 @@ fn main() {
     };
 
     let mut data = [0u8; 256];
-    let mut writer = &mut data.as_mut_slice();
+    let mut writer = data.as_mut_slice();
 
     let len = <_ as ZStructEncode>::z_len(&struct2);
     <_ as ZStructEncode>::z_encode(&struct2, &mut writer).unwrap();
@@ -163,14 +143,14 @@ fn main() {
     };
 
     let mut data = [0u8; 256];
-    let mut writer = &mut data.as_mut_slice();
+    let mut writer = data.as_mut_slice();
 
     let len = <_ as ZStructEncode>::z_len(&struct3);
     <_ as ZStructEncode>::z_encode(&struct3, &mut writer).unwrap();
 
     let mut reader = data.as_slice();
     let header = reader.peek_u8().unwrap();
-    assert_eq!((header & 0b1111_0000) >> 4, ZStruct3::ID);
+    assert!(ZStruct3::matches_header(header));
 
     let decoded_struct3 = <_ as ZStructDecode>::z_decode(&mut reader.sub(len).unwrap()).unwrap();
     assert_eq!(struct3, decoded_struct3);
@@ -0,0 +1,91 @@
+use core::marker::PhantomData;
+use std::io;
+
+use bytes::{Buf, BufMut, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{ZBytesReader, ZStructDecode, ZStructEncode};
+
+/// Frames Zenoh batches over a stream, as Zenoh does over TCP: each batch is prefixed with its
+/// length as a little-endian `u16`.
+///
+/// Any `ZStructEncode` value can be sent. Received batches are decoded into `T` with a
+/// `ZBytesReader`, so `T` cannot borrow from the stream: its payloads are `Bytes` (or owned
+/// types).
+#[derive(Debug)]
+pub struct ZBatchCodec<T> {
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> ZBatchCodec<T> {
+    pub const fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for ZBatchCodec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for ZBatchCodec<T> {
+    fn clone(&self) -> Self {
+        Self::new()
+    }
+}
+
+const LEN_SIZE: usize = size_of::<u16>();
+
+impl<T, E: ZStructEncode> Encoder<E> for ZBatchCodec<T> {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: E, dst: &mut BytesMut) -> io::Result<()> {
+        let start = dst.len();
+        dst.put_u16_le(0);
+
+        if let Err(e) = <E as ZStructEncode>::z_encode(&item, dst) {
+            dst.truncate(start);
+            return Err(e.into());
+        }
+
+        let Ok(len) = u16::try_from(dst.len() - start - LEN_SIZE) else {
+            dst.truncate(start);
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "batch exceeds u16::MAX bytes",
+            ));
+        };
+
+        dst[start..start + LEN_SIZE].copy_from_slice(&len.to_le_bytes());
+
+        Ok(())
+    }
+}
+
+impl<T: for<'a> ZStructDecode<'a>> Decoder for ZBatchCodec<T> {
+    type Item = T;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<T>> {
+        let Some(len) = src.get(..LEN_SIZE) else {
+            return Ok(None);
+        };
+
+        let len = u16::from_le_bytes([len[0], len[1]]) as usize;
+
+        if src.len() < LEN_SIZE + len {
+            src.reserve(LEN_SIZE + len - src.len());
+            return Ok(None);
+        }
+
+        src.advance(LEN_SIZE);
+        let batch = src.split_to(len).freeze();
+
+        <T as ZStructDecode>::z_decode_exact(&mut ZBytesReader::new(&batch))
+            .map(Some)
+            .map_err(io::Error::from)
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{ZCodecError, ZReader, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriter};
+use crate::{
+    ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZVectoredWriter, ZWriterExt,
+};
 
 const KIND_MASK: u8 = 0b0110_0000;
 
@@ -24,7 +26,7 @@ impl TryFrom<u8> for ZExtKind {
             0b0000_0000 => Ok(ZExtKind::Unit),
             0b0010_0000 => Ok(ZExtKind::U64),
             0b0100_0000 => Ok(ZExtKind::ZStruct),
-            _ => Err(ZCodecError::CouldNotParse),
+            _ => Err(ZCodecError::InvalidExtKind),
         }
     }
 }
@@ -41,42 +43,65 @@ pub fn zext_len<'a, T: ZExt<'a>>(x: &T) -> usize {
     1 + match T::KIND {
         ZExtKind::Unit | ZExtKind::U64 => <T as ZStructEncode>::z_len(x),
         ZExtKind::ZStruct => {
-            <usize as ZStructEncode>::z_len(&<T as ZStructEncode>::z_len(x))
-                + <T as ZStructEncode>::z_len(x)
+            let len = <T as ZStructEncode>::z_len(x);
+            <usize as ZStructEncode>::z_len(&len) + len
         }
     }
 }
 
-pub fn zext_encode<'a, T: ZExt<'a>, const ID: u8, const MANDATORY: bool>(
-    x: &T,
-    w: &mut ZWriter,
+fn zext_encode_header<'a, T: ZExt<'a>, W: ZWriterExt>(
+    w: &mut W,
+    id: u8,
+    mandatory: bool,
     more: bool,
 ) -> ZResult<()> {
-    let header: u8 = (ID | T::KIND as u8)
-        | if MANDATORY { FLAG_MANDATORY } else { 0 }
+    let header: u8 = (id | T::KIND as u8)
+        | if mandatory { FLAG_MANDATORY } else { 0 }
         | if more { FLAG_MORE } else { 0 };
 
-    <u8 as ZStructEncode>::z_encode(&header, w)?;
+    <u8 as ZStructEncode>::z_encode(&header, w)
+}
+
+pub fn zext_encode<'a, T: ZExt<'a>, W: ZWriterExt, const ID: u8, const MANDATORY: bool>(
+    x: &T,
+    w: &mut W,
+    more: bool,
+) -> ZResult<()> {
+    zext_encode_header::<T, W>(w, ID, MANDATORY, more)?;
 
     if T::KIND == ZExtKind::ZStruct {
-        <usize as ZStructEncode>::z_encode(&<T as ZStructEncode>::z_len(x), w)?;
+        w.write_prefixed(|w| <T as ZStructEncode>::z_encode(x, w))
+    } else {
+        <T as ZStructEncode>::z_encode(x, w)
     }
+}
+
+pub fn zext_encode_vectored<'a, 's, T: ZExt<'a>, const ID: u8, const MANDATORY: bool>(
+    x: &'s T,
+    w: &mut ZVectoredWriter<'s, '_>,
+    more: bool,
+) -> ZResult<()> {
+    zext_encode_header::<T, _>(w, ID, MANDATORY, more)?;
 
-    <T as ZStructEncode>::z_encode(x, w)
+    if T::KIND == ZExtKind::ZStruct {
+        w.write_prefixed(|w| <T as ZStructEncode>::z_encode_vectored(x, w))
+    } else {
+        <T as ZStructEncode>::z_encode_vectored(x, w)
+    }
 }
 
-pub fn zext_decode<'a, T: ZExt<'a>>(r: &mut ZReader<'a>) -> ZResult<T> {
+pub fn zext_decode<'a, T: ZExt<'a>, R: ZReaderExt<'a>>(r: &mut R) -> ZResult<T> {
     let _ = <u8 as ZStructDecode>::z_decode(r)?;
 
     if T::KIND == ZExtKind::ZStruct {
         let len = <usize as ZStructDecode>::z_decode(r)?;
-        <T as ZStructDecode>::z_decode(&mut <ZReader as ZReaderExt>::sub(r, len)?)
+        <T as ZStructDecode>::z_decode_exact(&mut r.sub(len)?)
     } else {
         <T as ZStructDecode>::z_decode(r)
     }
 }
 
-pub fn skip_ext(r: &mut ZReader, kind: ZExtKind) -> ZResult<()> {
+pub fn skip_ext<'a, R: ZReaderExt<'a>>(r: &mut R, kind: ZExtKind) -> ZResult<()> {
     let _ = <u8 as ZStructDecode>::z_decode(r)?;
 
     match kind {
@@ -86,14 +111,14 @@ pub fn skip_ext(r: &mut ZReader, kind: ZExtKind) -> ZResult<()> {
         }
         ZExtKind::ZStruct => {
             let len = <usize as ZStructDecode>::z_decode(r)?;
-            let _ = <ZReader as ZReaderExt>::sub(r, len)?;
+            let _ = r.sub(len)?;
         }
     }
 
     Ok(())
 }
 
-pub fn decode_ext_header(r: &mut ZReader) -> ZResult<(u8, ZExtKind, bool, bool)> {
+pub fn decode_ext_header<'a, R: ZReaderExt<'a>>(r: &mut R) -> ZResult<(u8, ZExtKind, bool, bool)> {
     let header = r.peek_u8()?;
 
     let id = header & ID_MASK;
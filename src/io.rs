@@ -0,0 +1,108 @@
+use std::{
+    io::{self, Read, Write},
+    vec::Vec,
+};
+
+use crate::{ZCodecError, ZStructDecode, ZStructEncode, r#struct::uint::VLE_LEN_MAX};
+
+impl From<ZCodecError> for io::Error {
+    fn from(err: ZCodecError) -> Self {
+        let kind = match err {
+            ZCodecError::CouldNotRead => io::ErrorKind::UnexpectedEof,
+            ZCodecError::CouldNotWrite | ZCodecError::BufferTooSmall { .. } => {
+                io::ErrorKind::WriteZero
+            }
+            ZCodecError::FieldExceedsReservedSize => io::ErrorKind::InvalidInput,
+            ZCodecError::UnknownMandatoryExtension { .. } => io::ErrorKind::Unsupported,
+            ZCodecError::CouldNotParse
+            | ZCodecError::InvalidUtf8
+            | ZCodecError::VleOverflow
+            | ZCodecError::IntegerOutOfRange
+            | ZCodecError::HeaderMismatch
+            | ZCodecError::TrailingBytes
+            | ZCodecError::UnknownMessage { .. }
+            | ZCodecError::InvalidExtKind => io::ErrorKind::InvalidData,
+            ZCodecError::Io => io::ErrorKind::Other,
+        };
+
+        io::Error::new(kind, err)
+    }
+}
+
+/// Gives back the `ZCodecError` wrapped by an `io::Error`, or maps its kind otherwise: reaching
+/// the end of the source is a short read, other failures are reported as `Io`.
+impl From<io::Error> for ZCodecError {
+    fn from(err: io::Error) -> Self {
+        if let Some(err) = err.get_ref().and_then(|e| e.downcast_ref::<ZCodecError>()) {
+            return *err;
+        }
+
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => ZCodecError::CouldNotRead,
+            io::ErrorKind::WriteZero => ZCodecError::CouldNotWrite,
+            io::ErrorKind::InvalidData => ZCodecError::CouldNotParse,
+            _ => ZCodecError::Io,
+        }
+    }
+}
+
+/// Largest message accepted by [`decode_from_reader`] (and written by [`encode_to_writer`]), so
+/// that a length prefix sent by a peer cannot make it allocate an arbitrary amount of memory.
+pub const FRAME_LEN_MAX: usize = 1 << 24;
+
+/// Writes `x` to `w`, prefixed with its length as a VLE `usize`.
+pub fn encode_to_writer<T: ZStructEncode>(x: &T, w: &mut impl Write) -> io::Result<()> {
+    let len = <T as ZStructEncode>::z_len(x);
+
+    if len > FRAME_LEN_MAX {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "message exceeds FRAME_LEN_MAX bytes",
+        ));
+    }
+
+    let mut data = Vec::with_capacity(VLE_LEN_MAX + len);
+    <usize as ZStructEncode>::z_encode(&len, &mut data)?;
+    <T as ZStructEncode>::z_encode(x, &mut data)?;
+
+    w.write_all(&data)
+}
+
+/// Reads one message written by [`encode_to_writer`] from `r`.
+///
+/// Exactly the bytes of the length prefix and of the message are pulled from `r`, so several
+/// messages can be read back to back. The message is buffered in `buf`, which the decoded value
+/// may borrow from. Messages longer than [`FRAME_LEN_MAX`] are rejected before being read.
+pub fn decode_from_reader<'a, T: ZStructDecode<'a>>(
+    r: &mut impl Read,
+    buf: &'a mut Vec<u8>,
+) -> io::Result<T> {
+    let mut prefix = [0u8; VLE_LEN_MAX];
+    let mut n = 0;
+
+    loop {
+        r.read_exact(&mut prefix[n..n + 1])?;
+        n += 1;
+
+        if prefix[n - 1] & 0x80 == 0 || n == VLE_LEN_MAX {
+            break;
+        }
+    }
+
+    let len = <usize as ZStructDecode>::z_decode(&mut &prefix[..n])?;
+
+    if len > FRAME_LEN_MAX {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "message exceeds FRAME_LEN_MAX bytes",
+        ));
+    }
+
+    // The buffer only grows as bytes arrive, whatever length the peer announced.
+    buf.clear();
+    if r.take(len as u64).read_to_end(buf)? != len {
+        return Err(ZCodecError::CouldNotRead.into());
+    }
+
+    Ok(<T as ZStructDecode>::z_decode_exact(&mut buf.as_slice())?)
+}
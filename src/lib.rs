@@ -1,5 +1,10 @@
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 pub use zenoh_codec_derive::*;
 
 #[cfg(test)]
@@ -8,184 +13,126 @@ mod tests;
 pub mod r#struct;
 pub use r#struct::*;
 
+#[cfg(feature = "tokio")]
+pub mod codec;
+#[cfg(feature = "tokio")]
+pub use codec::*;
+
 pub mod ext;
 pub use ext::*;
 
-pub type ZReader<'a> = &'a [u8];
-pub type ZWriter<'a> = &'a mut [u8];
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub use io::*;
+
+pub mod owned;
+pub use owned::*;
+
+pub mod reader;
+pub use reader::*;
+
+pub mod writer;
+pub use writer::*;
 
+/// Errors returned when encoding or decoding.
+///
+/// Each variant has a fixed discriminant, returned by [`ZCodecError::code`], that can be used as
+/// a compact and stable label, e.g. in metrics. Because some variants carry data, the error itself
+/// is two words wide rather than a single byte.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum ZCodecError {
+    /// A reader ran out of bytes, or could not provide them contiguously.
     CouldNotRead = 0,
+    /// A writer failed for another reason than a lack of room, e.g. it ran out of segments.
     CouldNotWrite = 1,
+    /// A value is not valid for its type, e.g. a `bool` that is neither 0 nor 1.
     CouldNotParse = 2,
-
+    /// A field does not fit in the room reserved for it, e.g. a length in its header slot.
     FieldExceedsReservedSize = 3,
-
-    UnsupportedMandatoryExtension = 4,
+    /// An extension marked as mandatory has an ID this decoder does not know.
+    UnknownMandatoryExtension { id: u8 } = 4,
+    /// A string field is not valid UTF-8.
+    InvalidUtf8 = 5,
+    /// A VLE-encoded integer uses more bytes than allowed.
+    VleOverflow = 6,
+    /// An integer does not fit in the type or the slot it is stored in.
+    IntegerOutOfRange = 7,
+    /// The fixed values of a header do not match the expected ones.
+    HeaderMismatch = 8,
+    /// A writer does not have room for the value being encoded, which needs `required` bytes.
+    BufferTooSmall { required: usize } = 9,
+    /// Bytes are left in the reader once decoding is done.
+    TrailingBytes = 10,
+    /// No variant of a `ZEnum` matches the header of the message being decoded.
+    UnknownMessage { header: u8 } = 11,
+    /// The header of an extension declares a kind other than unit, `u64` or struct.
+    InvalidExtKind = 12,
+    /// An I/O source or sink failed for another reason than reaching its end.
+    Io = 13,
 }
 
-pub type ZResult<T> = core::result::Result<T, ZCodecError>;
-
-pub trait ZReaderExt<'a> {
-    fn mark(&self) -> &'a [u8];
-    fn rewind(&mut self, mark: &'a [u8]);
-
-    fn remaining(&self) -> usize;
-    fn can_read(&self) -> bool {
-        self.remaining().gt(&0)
-    }
-
-    fn peek_u8(&self) -> ZResult<u8>;
-
-    fn read(&mut self, len: usize) -> ZResult<&'a [u8]>;
-    fn read_u8(&mut self) -> ZResult<u8>;
-
-    fn read_into(&mut self, dst: &'_ mut [u8]) -> ZResult<usize>;
-
-    fn sub(&mut self, len: usize) -> ZResult<ZReader<'a>> {
-        let sub = self.read(len)?;
-        Ok(sub)
+impl ZCodecError {
+    pub const fn code(&self) -> u8 {
+        match self {
+            ZCodecError::CouldNotRead => 0,
+            ZCodecError::CouldNotWrite => 1,
+            ZCodecError::CouldNotParse => 2,
+            ZCodecError::FieldExceedsReservedSize => 3,
+            ZCodecError::UnknownMandatoryExtension { .. } => 4,
+            ZCodecError::InvalidUtf8 => 5,
+            ZCodecError::VleOverflow => 6,
+            ZCodecError::IntegerOutOfRange => 7,
+            ZCodecError::HeaderMismatch => 8,
+            ZCodecError::BufferTooSmall { .. } => 9,
+            ZCodecError::TrailingBytes => 10,
+            ZCodecError::UnknownMessage { .. } => 11,
+            ZCodecError::InvalidExtKind => 12,
+            ZCodecError::Io => 13,
+        }
     }
 }
 
-pub trait ZWriterExt<'a> {
-    fn remaining(&self) -> usize;
-
-    fn write(&mut self, src: &'_ [u8]) -> ZResult<usize>;
-    fn write_u8(&mut self, value: u8) -> ZResult<()>;
-
-    fn write_exact(&mut self, src: &'_ [u8]) -> ZResult<()>;
-    fn write_slot(
-        &mut self,
-        len: usize,
-        writer: impl FnOnce(&'_ mut [u8]) -> usize,
-    ) -> ZResult<&'a [u8]>;
+/// Completes a `BufferTooSmall` error raised while encoding `x` with the length of `x`, so that
+/// it gives the room needed by the whole value rather than by the write that failed. Derived
+/// encoders call it, the outermost value being the last to complete it.
+pub fn z_encode_sized<T: ZStructEncode + ?Sized>(x: &T, result: ZResult<()>) -> ZResult<()> {
+    result.map_err(|err| match err {
+        ZCodecError::BufferTooSmall { .. } => ZCodecError::BufferTooSmall {
+            required: x.z_len(),
+        },
+        err => err,
+    })
 }
 
-impl<'a> ZReaderExt<'a> for ZReader<'a> {
-    fn mark(&self) -> &'a [u8] {
-        self
-    }
-
-    fn rewind(&mut self, mark: &'a [u8]) {
-        *self = mark;
-    }
-
-    fn remaining(&self) -> usize {
-        self.len()
-    }
-
-    fn peek_u8(&self) -> ZResult<u8> {
-        if !self.can_read() {
-            return Err(ZCodecError::CouldNotRead);
+impl core::fmt::Display for ZCodecError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ZCodecError::CouldNotRead => write!(f, "could not read"),
+            ZCodecError::CouldNotWrite => write!(f, "could not write"),
+            ZCodecError::CouldNotParse => write!(f, "could not parse"),
+            ZCodecError::FieldExceedsReservedSize => write!(f, "field exceeds reserved size"),
+            ZCodecError::UnknownMandatoryExtension { id } => {
+                write!(f, "unknown mandatory extension {id:#x}")
+            }
+            ZCodecError::InvalidUtf8 => write!(f, "invalid UTF-8"),
+            ZCodecError::VleOverflow => write!(f, "VLE overflow"),
+            ZCodecError::IntegerOutOfRange => write!(f, "integer out of range"),
+            ZCodecError::HeaderMismatch => write!(f, "header mismatch"),
+            ZCodecError::BufferTooSmall { required } => {
+                write!(f, "buffer too small, {required} bytes required")
+            }
+            ZCodecError::TrailingBytes => write!(f, "trailing bytes"),
+            ZCodecError::UnknownMessage { header } => {
+                write!(f, "unknown message with header {header:#04x}")
+            }
+            ZCodecError::InvalidExtKind => write!(f, "invalid extension kind"),
+            ZCodecError::Io => write!(f, "I/O error"),
         }
-
-        Ok(unsafe { *self.get_unchecked(0) })
-    }
-
-    fn read_u8(&mut self) -> ZResult<u8> {
-        if !self.can_read() {
-            return Err(ZCodecError::CouldNotRead);
-        }
-
-        let value = unsafe { *self.get_unchecked(0) };
-        *self = unsafe { self.get_unchecked(1..) };
-
-        Ok(value)
-    }
-
-    fn read_into(&mut self, dst: &'_ mut [u8]) -> ZResult<usize> {
-        let len = self.remaining().min(dst.len());
-        if len == 0 {
-            return Err(ZCodecError::CouldNotRead);
-        }
-
-        let (to_write, remain) = unsafe { self.split_at_unchecked(len) };
-        unsafe {
-            dst.get_unchecked_mut(..len).copy_from_slice(to_write);
-        }
-
-        *self = remain;
-
-        Ok(len)
-    }
-
-    fn read(&mut self, len: usize) -> ZResult<&'a [u8]> {
-        if self.len() < len {
-            return Err(ZCodecError::CouldNotRead);
-        }
-
-        let (zbuf, remain) = unsafe { self.split_at_unchecked(len) };
-        *self = remain;
-
-        Ok(zbuf)
     }
 }
 
-impl<'a> ZWriterExt<'a> for ZWriter<'a> {
-    fn remaining(&self) -> usize {
-        self.len()
-    }
-
-    fn write_u8(&mut self, value: u8) -> ZResult<()> {
-        if self.is_empty() {
-            return Err(ZCodecError::CouldNotWrite);
-        }
-
-        unsafe {
-            *self.get_unchecked_mut(0) = value;
-            *self = core::mem::take(self).get_unchecked_mut(1..);
-        }
-
-        Ok(())
-    }
-
-    fn write(&mut self, src: &[u8]) -> ZResult<usize> {
-        if src.is_empty() {
-            return Ok(0);
-        }
-        let len = self.len().min(src.len());
-        if len == 0 {
-            return Err(ZCodecError::CouldNotWrite);
-        }
-
-        let (to_write, remain) = unsafe { core::mem::take(self).split_at_mut_unchecked(len) };
-        to_write.copy_from_slice(unsafe { src.get_unchecked(..len) });
-        *self = remain;
-
-        Ok(len)
-    }
+impl core::error::Error for ZCodecError {}
 
-    fn write_exact(&mut self, src: &[u8]) -> ZResult<()> {
-        let len = self.write(src)?;
-
-        if len < src.len() {
-            return Err(ZCodecError::CouldNotWrite);
-        }
-
-        Ok(())
-    }
-
-    fn write_slot(
-        &mut self,
-        len: usize,
-        writer: impl FnOnce(&mut [u8]) -> usize,
-    ) -> ZResult<&'a [u8]> {
-        if self.len() < len {
-            return Err(ZCodecError::CouldNotWrite);
-        }
-
-        let written = writer(unsafe { self.get_unchecked_mut(..len) });
-
-        if written > len {
-            return Err(ZCodecError::CouldNotWrite);
-        }
-
-        let (slot, remain) = unsafe { core::mem::take(self).split_at_mut_unchecked(written) };
-        *self = remain;
-
-        Ok(slot)
-    }
-}
+pub type ZResult<T> = core::result::Result<T, ZCodecError>;
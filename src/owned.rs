@@ -0,0 +1,196 @@
+/// Conversion between a (possibly borrowing) value and its `'static` owned counterpart.
+///
+/// It is implemented for every field type and is what `#[zenoh(owned = ...)]` relies on to
+/// generate companion structs: the companion field type is `<T as ZIntoOwned>::Owned`.
+pub trait ZIntoOwned<'a>: Sized {
+    type Owned: 'static;
+
+    fn into_owned(self) -> Self::Owned;
+    fn as_borrowed(owned: &'a Self::Owned) -> Self;
+}
+
+macro_rules! zidentity {
+    ($($ty:ty),*) => {
+        $(
+            impl<'a> ZIntoOwned<'a> for $ty {
+                type Owned = $ty;
+
+                fn into_owned(self) -> Self::Owned {
+                    self
+                }
+
+                fn as_borrowed(owned: &'a Self::Owned) -> Self {
+                    *owned
+                }
+            }
+        )*
+    };
+}
+
+zidentity!(
+    u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, bool, f32, f64
+);
+
+impl<'a, const N: usize> ZIntoOwned<'a> for [u8; N] {
+    type Owned = [u8; N];
+
+    fn into_owned(self) -> Self::Owned {
+        self
+    }
+
+    fn as_borrowed(owned: &'a Self::Owned) -> Self {
+        *owned
+    }
+}
+
+impl<'a, T: ZIntoOwned<'a>> ZIntoOwned<'a> for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(T::into_owned)
+    }
+
+    fn as_borrowed(owned: &'a Self::Owned) -> Self {
+        owned.as_ref().map(T::as_borrowed)
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod alloc {
+    use alloc::{
+        borrow::{Cow, ToOwned},
+        boxed::Box,
+        string::String,
+        vec::Vec,
+    };
+
+    use super::ZIntoOwned;
+
+    impl<'a> ZIntoOwned<'a> for &'a str {
+        type Owned = String;
+
+        fn into_owned(self) -> Self::Owned {
+            self.to_owned()
+        }
+
+        fn as_borrowed(owned: &'a Self::Owned) -> Self {
+            owned
+        }
+    }
+
+    impl<'a> ZIntoOwned<'a> for &'a [u8] {
+        type Owned = Vec<u8>;
+
+        fn into_owned(self) -> Self::Owned {
+            self.to_owned()
+        }
+
+        fn as_borrowed(owned: &'a Self::Owned) -> Self {
+            owned
+        }
+    }
+
+    impl<'a> ZIntoOwned<'a> for String {
+        type Owned = String;
+
+        fn into_owned(self) -> Self::Owned {
+            self
+        }
+
+        fn as_borrowed(owned: &'a Self::Owned) -> Self {
+            owned.clone()
+        }
+    }
+
+    impl<'a> ZIntoOwned<'a> for Vec<u8> {
+        type Owned = Vec<u8>;
+
+        fn into_owned(self) -> Self::Owned {
+            self
+        }
+
+        fn as_borrowed(owned: &'a Self::Owned) -> Self {
+            owned.clone()
+        }
+    }
+
+    impl<'a, T: ZIntoOwned<'a>> ZIntoOwned<'a> for Box<T> {
+        type Owned = Box<T::Owned>;
+
+        fn into_owned(self) -> Self::Owned {
+            Box::new(T::into_owned(*self))
+        }
+
+        fn as_borrowed(owned: &'a Self::Owned) -> Self {
+            Box::new(T::as_borrowed(owned))
+        }
+    }
+
+    impl<'a> ZIntoOwned<'a> for Cow<'a, str> {
+        type Owned = String;
+
+        fn into_owned(self) -> String {
+            Cow::into_owned(self)
+        }
+
+        fn as_borrowed(owned: &'a String) -> Self {
+            Cow::Borrowed(owned)
+        }
+    }
+
+    impl<'a> ZIntoOwned<'a> for Cow<'a, [u8]> {
+        type Owned = Vec<u8>;
+
+        fn into_owned(self) -> Vec<u8> {
+            Cow::into_owned(self)
+        }
+
+        fn as_borrowed(owned: &'a Vec<u8>) -> Self {
+            Cow::Borrowed(owned)
+        }
+    }
+
+    #[cfg(feature = "bytes")]
+    impl<'a> ZIntoOwned<'a> for bytes::Bytes {
+        type Owned = bytes::Bytes;
+
+        fn into_owned(self) -> Self::Owned {
+            self
+        }
+
+        fn as_borrowed(owned: &'a Self::Owned) -> Self {
+            owned.clone()
+        }
+    }
+}
+
+#[cfg(feature = "heapless")]
+mod heapless {
+    use heapless::{String, Vec};
+
+    use super::ZIntoOwned;
+
+    impl<'a, const N: usize> ZIntoOwned<'a> for String<N> {
+        type Owned = String<N>;
+
+        fn into_owned(self) -> Self::Owned {
+            self
+        }
+
+        fn as_borrowed(owned: &'a Self::Owned) -> Self {
+            owned.clone()
+        }
+    }
+
+    impl<'a, const N: usize> ZIntoOwned<'a> for Vec<u8, N> {
+        type Owned = Vec<u8, N>;
+
+        fn into_owned(self) -> Self::Owned {
+            self
+        }
+
+        fn as_borrowed(owned: &'a Self::Owned) -> Self {
+            owned.clone()
+        }
+    }
+}
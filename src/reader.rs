@@ -0,0 +1,130 @@
+use crate::{ZCodecError, ZResult};
+
+#[cfg(feature = "bytes")]
+mod bytes;
+#[cfg(feature = "bytes")]
+pub use bytes::*;
+mod chunks;
+pub use chunks::*;
+mod trace;
+pub use trace::*;
+
+pub type ZReader<'a> = &'a [u8];
+
+pub trait ZReaderExt<'a>: Sized + Clone {
+    fn mark(&self) -> Self {
+        self.clone()
+    }
+
+    fn rewind(&mut self, mark: Self) {
+        *self = mark;
+    }
+
+    fn remaining(&self) -> usize;
+    fn can_read(&self) -> bool {
+        self.remaining().gt(&0)
+    }
+
+    fn peek_u8(&self) -> ZResult<u8>;
+
+    fn read(&mut self, len: usize) -> ZResult<&'a [u8]>;
+    fn read_u8(&mut self) -> ZResult<u8>;
+
+    fn read_into(&mut self, dst: &'_ mut [u8]) -> ZResult<usize>;
+
+    fn sub(&mut self, len: usize) -> ZResult<Self>;
+
+    /// Absolute offset of the reader in the buffer being decoded, for readers that track it
+    /// (`ZTraceReader`, `ZBytesReader`). Others return 0.
+    fn offset(&self) -> usize {
+        0
+    }
+
+    /// Called by derived decoders when `field` of `r#struct`, starting at `offset`, fails to
+    /// decode with `err`, then again for each enclosing field. `ZTraceReader` records it, other
+    /// readers ignore it.
+    fn trace(
+        &self,
+        err: ZCodecError,
+        offset: usize,
+        r#struct: &'static str,
+        field: &'static str,
+    ) -> ZCodecError {
+        let _ = (offset, r#struct, field);
+
+        err
+    }
+
+    /// Reads `len` bytes as `Bytes`. `ZBytesReader` returns a sub-slice of its source without
+    /// copy, other readers copy the bytes out.
+    #[cfg(feature = "bytes")]
+    fn read_bytes(&mut self, len: usize) -> ZResult<::bytes::Bytes> {
+        if self.remaining() < len {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        let mut dst = ::bytes::BytesMut::zeroed(len);
+
+        if len != 0 {
+            self.read_into(&mut dst)?;
+        }
+
+        Ok(dst.freeze())
+    }
+}
+
+impl<'a> ZReaderExt<'a> for ZReader<'a> {
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    fn peek_u8(&self) -> ZResult<u8> {
+        if !self.can_read() {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        Ok(unsafe { *self.get_unchecked(0) })
+    }
+
+    fn read_u8(&mut self) -> ZResult<u8> {
+        if !self.can_read() {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        let value = unsafe { *self.get_unchecked(0) };
+        *self = unsafe { self.get_unchecked(1..) };
+
+        Ok(value)
+    }
+
+    fn read_into(&mut self, dst: &'_ mut [u8]) -> ZResult<usize> {
+        let len = self.remaining().min(dst.len());
+        if len == 0 {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        let (to_write, remain) = unsafe { self.split_at_unchecked(len) };
+        unsafe {
+            dst.get_unchecked_mut(..len).copy_from_slice(to_write);
+        }
+
+        *self = remain;
+
+        Ok(len)
+    }
+
+    fn read(&mut self, len: usize) -> ZResult<&'a [u8]> {
+        if self.len() < len {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        let (zbuf, remain) = unsafe { self.split_at_unchecked(len) };
+        *self = remain;
+
+        Ok(zbuf)
+    }
+
+    fn sub(&mut self, len: usize) -> ZResult<Self> {
+        self.read(len)
+    }
+}
@@ -0,0 +1,61 @@
+use bytes::Bytes;
+
+use crate::{ZReaderExt, ZResult};
+
+/// A reader over a `Bytes` buffer.
+///
+/// `Bytes` fields are decoded as sub-slices of the source buffer (`Bytes::slice_ref`), without
+/// copy, so they can outlive the reader and the batch they come from.
+#[derive(Debug, Clone, Copy)]
+pub struct ZBytesReader<'a> {
+    source: &'a Bytes,
+    cursor: &'a [u8],
+}
+
+impl<'a> ZBytesReader<'a> {
+    pub fn new(source: &'a Bytes) -> Self {
+        Self {
+            source,
+            cursor: source,
+        }
+    }
+}
+
+impl<'a> ZReaderExt<'a> for ZBytesReader<'a> {
+    fn remaining(&self) -> usize {
+        self.cursor.remaining()
+    }
+
+    fn peek_u8(&self) -> ZResult<u8> {
+        self.cursor.peek_u8()
+    }
+
+    fn read(&mut self, len: usize) -> ZResult<&'a [u8]> {
+        self.cursor.read(len)
+    }
+
+    fn read_u8(&mut self) -> ZResult<u8> {
+        self.cursor.read_u8()
+    }
+
+    fn read_into(&mut self, dst: &'_ mut [u8]) -> ZResult<usize> {
+        self.cursor.read_into(dst)
+    }
+
+    fn sub(&mut self, len: usize) -> ZResult<Self> {
+        Ok(Self {
+            source: self.source,
+            cursor: self.cursor.read(len)?,
+        })
+    }
+
+    fn offset(&self) -> usize {
+        self.cursor.as_ptr() as usize - self.source.as_ptr() as usize
+    }
+
+    fn read_bytes(&mut self, len: usize) -> ZResult<Bytes> {
+        let slice = self.cursor.read(len)?;
+
+        Ok(self.source.slice_ref(slice))
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{ZCodecError, ZReaderExt, ZResult};
+
+/// A reader spanning several non-contiguous chunks, such as the slots of a ring buffer.
+///
+/// Borrowed reads (`&[u8]`, `&str`) stay zero-copy as long as the requested bytes sit inside a
+/// single chunk. A borrowed read that crosses a chunk boundary fails with `CouldNotRead`.
+#[derive(Debug, Clone, Copy)]
+pub struct ZChunkedReader<'a> {
+    head: &'a [u8],
+    tail: &'a [&'a [u8]],
+    len: usize,
+}
+
+impl<'a> ZChunkedReader<'a> {
+    pub fn new(chunks: &'a [&'a [u8]]) -> Self {
+        Self {
+            head: &[],
+            tail: chunks,
+            len: chunks.iter().map(|chunk| chunk.len()).sum(),
+        }
+    }
+
+    fn skip_empty(&mut self) {
+        while self.head.is_empty() {
+            match self.tail.split_first() {
+                Some((head, tail)) => {
+                    self.head = head;
+                    self.tail = tail;
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn advance(&mut self, mut len: usize) {
+        self.len -= len;
+
+        while len > 0 {
+            self.skip_empty();
+
+            let n = self.head.len().min(len);
+            self.head = unsafe { self.head.get_unchecked(n..) };
+            len -= n;
+        }
+    }
+}
+
+impl<'a> ZReaderExt<'a> for ZChunkedReader<'a> {
+    fn remaining(&self) -> usize {
+        self.len
+    }
+
+    fn peek_u8(&self) -> ZResult<u8> {
+        if !self.can_read() {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        core::iter::once(self.head)
+            .chain(self.tail.iter().copied())
+            .find_map(|chunk| chunk.first().copied())
+            .ok_or(ZCodecError::CouldNotRead)
+    }
+
+    fn read(&mut self, len: usize) -> ZResult<&'a [u8]> {
+        if self.len < len {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        if len == 0 {
+            return Ok(&[]);
+        }
+
+        self.skip_empty();
+        if self.head.len() < len {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        let (zbuf, remain) = unsafe { self.head.split_at_unchecked(len) };
+        self.head = remain;
+        self.len -= len;
+
+        Ok(zbuf)
+    }
+
+    fn read_u8(&mut self) -> ZResult<u8> {
+        let value = self.peek_u8()?;
+        self.advance(1);
+
+        Ok(value)
+    }
+
+    fn read_into(&mut self, dst: &'_ mut [u8]) -> ZResult<usize> {
+        let len = self.len.min(dst.len());
+        if len == 0 {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        let mut written = 0;
+        while written < len {
+            self.skip_empty();
+
+            let n = self.head.len().min(len - written);
+            unsafe {
+                dst.get_unchecked_mut(written..written + n)
+                    .copy_from_slice(self.head.get_unchecked(..n));
+            }
+
+            self.head = unsafe { self.head.get_unchecked(n..) };
+            self.len -= n;
+            written += n;
+        }
+
+        Ok(len)
+    }
+
+    fn sub(&mut self, len: usize) -> ZResult<Self> {
+        if self.len < len {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        let sub = Self { len, ..*self };
+        self.advance(len);
+
+        Ok(sub)
+    }
+}
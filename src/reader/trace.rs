@@ -0,0 +1,186 @@
+use core::{cell::Cell, fmt};
+
+use crate::{ZCodecError, ZReaderExt, ZResult};
+
+/// Maximum number of nested fields recorded by a `ZTrace`. Deeper paths keep their innermost
+/// fields and the name of the outermost struct.
+pub const ZTRACE_DEPTH: usize = 8;
+
+/// Where a decoding failed: the outermost struct, the path of fields down to the one that
+/// failed and the offset at which that field started.
+///
+/// It is filled by a `ZTraceReader` and displays as `Msg1.ext1.keyexpr at byte 23`.
+#[derive(Debug)]
+pub struct ZTrace {
+    offset: Cell<usize>,
+    root: Cell<&'static str>,
+
+    // Innermost field first.
+    fields: [Cell<&'static str>; ZTRACE_DEPTH],
+    depth: Cell<usize>,
+}
+
+impl ZTrace {
+    pub const fn new() -> Self {
+        Self {
+            offset: Cell::new(0),
+            root: Cell::new(""),
+            fields: [const { Cell::new("") }; ZTRACE_DEPTH],
+            depth: Cell::new(0),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.depth.set(0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.depth.get() == 0
+    }
+
+    /// Offset of the field that failed, if any.
+    pub fn offset(&self) -> Option<usize> {
+        (!self.is_empty()).then(|| self.offset.get())
+    }
+
+    /// Outermost struct that failed to decode, if any.
+    pub fn root(&self) -> Option<&'static str> {
+        (!self.is_empty()).then(|| self.root.get())
+    }
+
+    /// Recorded fields, outermost first. Fields whose failure does not belong to a field of
+    /// their struct (e.g. an unknown mandatory extension) are skipped.
+    pub fn fields(&self) -> impl Iterator<Item = &'static str> + '_ {
+        let depth = self.depth.get().min(ZTRACE_DEPTH);
+
+        self.fields[..depth]
+            .iter()
+            .rev()
+            .map(Cell::get)
+            .filter(|field| !field.is_empty())
+    }
+
+    fn push(&self, offset: usize, r#struct: &'static str, field: &'static str) {
+        let depth = self.depth.get();
+
+        if depth == 0 {
+            self.offset.set(offset);
+        }
+
+        if let Some(slot) = self.fields.get(depth) {
+            slot.set(field);
+        }
+
+        self.root.set(r#struct);
+        self.depth.set(depth + 1);
+    }
+}
+
+impl Default for ZTrace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for ZTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(root) = self.root() else {
+            return write!(f, "no error");
+        };
+
+        write!(f, "{root}")?;
+
+        if self.depth.get() > ZTRACE_DEPTH {
+            write!(f, "..")?;
+        }
+
+        for field in self.fields() {
+            write!(f, ".{field}")?;
+        }
+
+        write!(f, " at byte {}", self.offset.get())
+    }
+}
+
+/// A reader that tracks its absolute offset and records in a `ZTrace` where decoding failed.
+///
+/// It wraps any other reader, so it can be swapped in to debug a failing decoding.
+#[derive(Debug, Clone, Copy)]
+pub struct ZTraceReader<'t, R> {
+    inner: R,
+    base: usize,
+    len: usize,
+    trace: &'t ZTrace,
+}
+
+impl<'a, 't, R: ZReaderExt<'a>> ZTraceReader<'t, R> {
+    /// Wraps `inner`, which starts at offset 0. `trace` is cleared.
+    pub fn new(inner: R, trace: &'t ZTrace) -> Self {
+        trace.clear();
+
+        Self {
+            base: 0,
+            len: inner.remaining(),
+            inner,
+            trace,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<'a, R: ZReaderExt<'a>> ZReaderExt<'a> for ZTraceReader<'_, R> {
+    fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    fn peek_u8(&self) -> ZResult<u8> {
+        self.inner.peek_u8()
+    }
+
+    fn read(&mut self, len: usize) -> ZResult<&'a [u8]> {
+        self.inner.read(len)
+    }
+
+    fn read_u8(&mut self) -> ZResult<u8> {
+        self.inner.read_u8()
+    }
+
+    fn read_into(&mut self, dst: &'_ mut [u8]) -> ZResult<usize> {
+        self.inner.read_into(dst)
+    }
+
+    fn sub(&mut self, len: usize) -> ZResult<Self> {
+        let base = self.offset();
+
+        Ok(Self {
+            inner: self.inner.sub(len)?,
+            base,
+            len,
+            trace: self.trace,
+        })
+    }
+
+    #[cfg(feature = "bytes")]
+    fn read_bytes(&mut self, len: usize) -> ZResult<::bytes::Bytes> {
+        self.inner.read_bytes(len)
+    }
+
+    fn offset(&self) -> usize {
+        self.base + (self.len - self.inner.remaining())
+    }
+
+    fn trace(
+        &self,
+        err: ZCodecError,
+        offset: usize,
+        r#struct: &'static str,
+        field: &'static str,
+    ) -> ZCodecError {
+        self.trace.push(offset, r#struct, field);
+
+        err
+    }
+}
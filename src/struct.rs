@@ -1,18 +1,59 @@
-use crate::{ZReader, ZResult, ZWriter};
+use crate::{ZCodecError, ZReaderExt, ZResult, ZVectoredWriter, ZWriterExt};
 
+#[cfg(feature = "alloc")]
+mod alloc;
 mod array;
+mod bool;
 mod bytes;
+mod float;
+#[cfg(feature = "heapless")]
+mod heapless;
+mod int;
+mod seq;
 mod str;
-mod uint;
+pub(crate) mod uint;
+
+pub use seq::{ZSeq, ZSeqFrom, ZSeqIter};
 
 pub trait ZStructEncode {
     fn z_len(&self) -> usize;
 
-    fn z_encode(&self, w: &mut ZWriter) -> ZResult<()>;
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()>;
+
+    /// Same as `z_encode`, except that payloads are recorded as borrowed segments of `w`
+    /// instead of being copied.
+    fn z_encode_vectored<'s>(&'s self, w: &mut ZVectoredWriter<'s, '_>) -> ZResult<()> {
+        self.z_encode(w)
+    }
 }
 
 pub trait ZStructDecode<'a> {
-    fn z_decode(r: &mut ZReader<'a>) -> ZResult<Self>
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self>
     where
         Self: Sized;
+
+    /// Same as `z_decode`, for a reader that holds exactly one value, such as a sized field or a
+    /// whole message. With the `strict` feature, bytes left in `r` are an error.
+    fn z_decode_exact<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self>
+    where
+        Self: Sized,
+    {
+        let x = Self::z_decode(r)?;
+
+        if cfg!(feature = "strict") && r.can_read() {
+            return Err(ZCodecError::TrailingBytes);
+        }
+
+        Ok(x)
+    }
+}
+
+/// Number of bits needed by the values of a header field, checked at compile time against the
+/// width of its slot. `#[derive(ZEnum)]` on fieldless enums shadows this default of 0, which
+/// skips the check, with an inherent `Z_BITS` constant.
+#[doc(hidden)]
+pub trait ZHeaderBits {
+    const Z_BITS: u32 = 0;
 }
+
+impl<T: ?Sized> ZHeaderBits for T {}
@@ -0,0 +1,121 @@
+use alloc::{borrow::Cow, boxed::Box, string::String, vec, vec::Vec};
+
+use crate::{
+    ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZVectoredWriter, ZWriterExt,
+};
+
+// Owned values are copied out of the reader, so unlike their borrowed counterparts they can
+// also be decoded when they span several chunks.
+fn read_to_vec<'a, R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Vec<u8>> {
+    let mut dst = vec![0u8; r.remaining()];
+
+    if !dst.is_empty() {
+        r.read_into(&mut dst)?;
+    }
+
+    Ok(dst)
+}
+
+impl ZStructEncode for Vec<u8> {
+    fn z_len(&self) -> usize {
+        self.len()
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        w.write_exact(self)
+    }
+
+    fn z_encode_vectored<'s>(&'s self, w: &mut ZVectoredWriter<'s, '_>) -> ZResult<()> {
+        w.write_borrowed(self)
+    }
+}
+
+impl<'a> ZStructDecode<'a> for Vec<u8> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        read_to_vec(r)
+    }
+}
+
+impl ZStructEncode for String {
+    fn z_len(&self) -> usize {
+        self.len()
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        w.write_exact(self.as_bytes())
+    }
+}
+
+impl<'a> ZStructDecode<'a> for String {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        String::from_utf8(read_to_vec(r)?).map_err(|_| ZCodecError::InvalidUtf8)
+    }
+}
+
+impl<T: ZStructEncode> ZStructEncode for Box<T> {
+    fn z_len(&self) -> usize {
+        <T as ZStructEncode>::z_len(self)
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        <T as ZStructEncode>::z_encode(self, w)
+    }
+
+    fn z_encode_vectored<'s>(&'s self, w: &mut ZVectoredWriter<'s, '_>) -> ZResult<()> {
+        <T as ZStructEncode>::z_encode_vectored(self, w)
+    }
+}
+
+impl<'a, T: ZStructDecode<'a>> ZStructDecode<'a> for Box<T> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        <T as ZStructDecode>::z_decode(r).map(Box::new)
+    }
+}
+
+impl ZStructEncode for Cow<'_, str> {
+    fn z_len(&self) -> usize {
+        self.len()
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        w.write_exact(self.as_bytes())
+    }
+}
+
+// Cows borrow from the reader when the value is contiguous, and fall back to a copy when it
+// spans several chunks.
+impl<'a> ZStructDecode<'a> for Cow<'a, str> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        match <&'a str as ZStructDecode>::z_decode(r) {
+            Err(ZCodecError::CouldNotRead) => {
+                <String as ZStructDecode>::z_decode(r).map(Cow::Owned)
+            }
+            result => result.map(Cow::Borrowed),
+        }
+    }
+}
+
+impl ZStructEncode for Cow<'_, [u8]> {
+    fn z_len(&self) -> usize {
+        self.len()
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        w.write_exact(self)
+    }
+
+    fn z_encode_vectored<'s>(&'s self, w: &mut ZVectoredWriter<'s, '_>) -> ZResult<()> {
+        w.write_borrowed(self)
+    }
+}
+
+impl<'a> ZStructDecode<'a> for Cow<'a, [u8]> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        match <&'a [u8] as ZStructDecode>::z_decode(r) {
+            Err(ZCodecError::CouldNotRead) => {
+                <Vec<u8> as ZStructDecode>::z_decode(r).map(Cow::Owned)
+            }
+            result => result.map(Cow::Borrowed),
+        }
+    }
+}
@@ -1,17 +1,22 @@
-use crate::{ZReader, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriter, ZWriterExt};
+use crate::{ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriterExt};
 
 impl<const N: usize> ZStructEncode for [u8; N] {
     fn z_len(&self) -> usize {
         N
     }
 
-    fn z_encode(&self, w: &mut ZWriter) -> ZResult<()> {
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
         w.write_exact(self.as_slice())
     }
 }
 
 impl<'a, const N: usize> ZStructDecode<'a> for [u8; N] {
-    fn z_decode(r: &mut ZReader<'a>) -> ZResult<Self> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        // Without the `strict` feature, a short read leaves the end of the array zeroed.
+        if cfg!(feature = "strict") && r.remaining() < N {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
         let mut dst = [0u8; N];
         r.read_into(dst.as_mut_slice())?;
         Ok(dst)
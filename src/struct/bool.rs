@@ -0,0 +1,21 @@
+use crate::{ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriterExt};
+
+impl ZStructEncode for bool {
+    fn z_len(&self) -> usize {
+        1
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        w.write_u8(*self as u8)
+    }
+}
+
+impl<'a> ZStructDecode<'a> for bool {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        match r.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(ZCodecError::CouldNotParse),
+        }
+    }
+}
@@ -1,17 +1,43 @@
-use crate::{ZReader, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriter, ZWriterExt};
+use crate::{ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZVectoredWriter, ZWriterExt};
 
 impl ZStructEncode for &[u8] {
     fn z_len(&self) -> usize {
         self.len()
     }
 
-    fn z_encode(&self, w: &mut ZWriter) -> ZResult<()> {
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
         w.write_exact(self)
     }
+
+    fn z_encode_vectored<'s>(&'s self, w: &mut ZVectoredWriter<'s, '_>) -> ZResult<()> {
+        w.write_borrowed(self)
+    }
 }
 
 impl<'a> ZStructDecode<'a> for &'a [u8] {
-    fn z_decode(r: &mut ZReader<'a>) -> ZResult<Self> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
         r.read(r.remaining())
     }
 }
+
+#[cfg(feature = "bytes")]
+impl ZStructEncode for bytes::Bytes {
+    fn z_len(&self) -> usize {
+        self.len()
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        w.write_exact(self)
+    }
+
+    fn z_encode_vectored<'s>(&'s self, w: &mut ZVectoredWriter<'s, '_>) -> ZResult<()> {
+        w.write_borrowed(self)
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<'a> ZStructDecode<'a> for bytes::Bytes {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        r.read_bytes(r.remaining())
+    }
+}
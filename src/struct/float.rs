@@ -0,0 +1,33 @@
+use crate::{ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriterExt};
+
+macro_rules! zfloat {
+    ($($ty:ty),*) => {
+        $(
+            // Encoded as fixed-width little-endian.
+            impl ZStructEncode for $ty {
+                fn z_len(&self) -> usize {
+                    size_of::<$ty>()
+                }
+
+                fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+                    w.write_exact(&self.to_le_bytes())
+                }
+            }
+
+            impl<'a> ZStructDecode<'a> for $ty {
+                fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+                    // Copied out rather than borrowed, the bytes may straddle chunks.
+                    let mut bytes = [0u8; size_of::<$ty>()];
+                    if r.remaining() < bytes.len() {
+                        return Err(ZCodecError::CouldNotRead);
+                    }
+
+                    r.read_into(&mut bytes)?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+zfloat!(f32, f64);
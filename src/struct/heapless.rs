@@ -0,0 +1,57 @@
+use heapless::{String, Vec};
+
+use crate::{
+    ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZVectoredWriter, ZWriterExt,
+};
+
+// Like their `alloc` counterparts, values are copied out of the reader. They fail with
+// `FieldExceedsReservedSize` when longer than their capacity.
+fn read_to_vec<'a, R: ZReaderExt<'a>, const N: usize>(r: &mut R) -> ZResult<Vec<u8, N>> {
+    let len = r.remaining();
+
+    let mut dst = Vec::new();
+    dst.resize(len, 0)
+        .map_err(|_| ZCodecError::FieldExceedsReservedSize)?;
+
+    if len != 0 {
+        r.read_into(&mut dst)?;
+    }
+
+    Ok(dst)
+}
+
+impl<const N: usize> ZStructEncode for Vec<u8, N> {
+    fn z_len(&self) -> usize {
+        self.len()
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        w.write_exact(self)
+    }
+
+    fn z_encode_vectored<'s>(&'s self, w: &mut ZVectoredWriter<'s, '_>) -> ZResult<()> {
+        w.write_borrowed(self)
+    }
+}
+
+impl<'a, const N: usize> ZStructDecode<'a> for Vec<u8, N> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        read_to_vec(r)
+    }
+}
+
+impl<const N: usize> ZStructEncode for String<N> {
+    fn z_len(&self) -> usize {
+        self.len()
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        w.write_exact(self.as_bytes())
+    }
+}
+
+impl<'a, const N: usize> ZStructDecode<'a> for String<N> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        String::from_utf8(read_to_vec(r)?).map_err(|_| ZCodecError::InvalidUtf8)
+    }
+}
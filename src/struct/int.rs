@@ -0,0 +1,41 @@
+use crate::{
+    ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriterExt,
+    r#struct::uint::vle_len,
+};
+
+/// Maps signed integers to unsigned ones so that small magnitudes, negative or not, stay small:
+/// 0, -1, 1, -2, 2... become 0, 1, 2, 3, 4...
+const fn zigzag_encode(x: i64) -> u64 {
+    ((x << 1) ^ (x >> 63)) as u64
+}
+
+const fn zigzag_decode(x: u64) -> i64 {
+    ((x >> 1) as i64) ^ -((x & 1) as i64)
+}
+
+macro_rules! zsint {
+    ($($ty:ty),*) => {
+        $(
+            // Encoded as a zig-zag VLE, so `i8` takes 1 or 2 bytes unlike `u8`.
+            impl ZStructEncode for $ty {
+                fn z_len(&self) -> usize {
+                    vle_len(zigzag_encode(*self as i64))
+                }
+
+                fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+                    let v = zigzag_encode(*self as i64);
+                    v.z_encode(w)
+                }
+            }
+
+            impl<'a> ZStructDecode<'a> for $ty {
+                fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+                    let v = zigzag_decode(<u64 as ZStructDecode>::z_decode(r)?);
+                    <$ty>::try_from(v).map_err(|_| ZCodecError::IntegerOutOfRange)
+                }
+            }
+        )*
+    };
+}
+
+zsint!(i8, i16, i32, i64, isize);
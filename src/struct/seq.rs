@@ -0,0 +1,285 @@
+use core::{fmt, slice};
+
+use crate::{
+    ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZVectoredWriter, ZWriterExt,
+    z_encode_sized,
+};
+
+/// A sequence of `T`, encoded as a VLE count followed by the elements, or as elements up to the
+/// end of the reader with `#[zenoh(size = remain)]`.
+///
+/// Elements are encoded back to back, so `T` must know where it ends (e.g. integers or structs
+/// whose last field is not `size = remain`), and must take at least one byte.
+///
+/// A sequence to encode is built from a slice with `ZSeq::new`, or from an iterator with
+/// `ZSeqFrom`. A decoded sequence borrows its
+/// encoded elements: their boundaries are checked on decode, then `iter` decodes them on demand,
+/// without allocating, and yields the result of each decoding. Like `&[u8]`, it must be contiguous in the reader.
+pub struct ZSeq<'a, T> {
+    repr: Repr<'a, T>,
+}
+
+enum Repr<'a, T> {
+    Slice(&'a [T]),
+    Encoded { len: usize, bytes: &'a [u8] },
+}
+
+impl<'a, T> ZSeq<'a, T> {
+    pub const fn new(slice: &'a [T]) -> Self {
+        Self {
+            repr: Repr::Slice(slice),
+        }
+    }
+
+    pub const fn len(&self) -> usize {
+        match self.repr {
+            Repr::Slice(slice) => slice.len(),
+            Repr::Encoded { len, .. } => len,
+        }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the elements, decoding them on demand for a decoded sequence. Iteration
+    /// stops after the first element that fails to decode.
+    pub fn iter(&self) -> ZSeqIter<'a, T>
+    where
+        T: ZStructDecode<'a> + Clone,
+    {
+        match self.repr {
+            Repr::Slice(slice) => ZSeqIter::Slice(slice.iter()),
+            Repr::Encoded { len, bytes } => ZSeqIter::Encoded { len, bytes },
+        }
+    }
+}
+
+impl<'a, T: ZStructEncode> ZSeq<'a, T> {
+    /// Length of the elements alone, as encoded with `#[zenoh(size = remain)]`.
+    pub fn z_len_remain(&self) -> usize {
+        match self.repr {
+            Repr::Slice(slice) => slice.iter().map(ZStructEncode::z_len).sum(),
+            Repr::Encoded { bytes, .. } => bytes.len(),
+        }
+    }
+
+    /// Encodes the elements alone, as with `#[zenoh(size = remain)]`.
+    pub fn z_encode_remain<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        match self.repr {
+            Repr::Slice(slice) => slice.iter().try_for_each(|x| x.z_encode(w)),
+            Repr::Encoded { bytes, .. } => w.write_exact(bytes),
+        }
+    }
+
+    /// Same as `z_encode_remain`, with the elements' vectored flavour.
+    pub fn z_encode_remain_vectored<'s>(&'s self, w: &mut ZVectoredWriter<'s, '_>) -> ZResult<()> {
+        match self.repr {
+            Repr::Slice(slice) => slice.iter().try_for_each(|x| x.z_encode_vectored(w)),
+            Repr::Encoded { bytes, .. } => w.write_borrowed(bytes),
+        }
+    }
+}
+
+impl<'a, T: ZStructDecode<'a>> ZSeq<'a, T> {
+    /// Decodes elements up to the end of `r`, as with `#[zenoh(size = remain)]`.
+    pub fn z_decode_remain<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        let mut probe = r.clone();
+        let mut len = 0;
+
+        while probe.can_read() {
+            skip::<T, R>(&mut probe)?;
+            len += 1;
+        }
+
+        let bytes = r.read(r.remaining())?;
+
+        Ok(Self {
+            repr: Repr::Encoded { len, bytes },
+        })
+    }
+}
+
+/// Checks the boundaries of the next element. An element that takes no byte is an error, as
+/// the number of elements could not be told from the bytes.
+fn skip<'a, T: ZStructDecode<'a>, R: ZReaderExt<'a>>(r: &mut R) -> ZResult<()> {
+    let remaining = r.remaining();
+    T::z_decode(r)?;
+
+    if r.remaining() == remaining {
+        return Err(ZCodecError::CouldNotParse);
+    }
+
+    Ok(())
+}
+
+impl<T: ZStructEncode> ZStructEncode for ZSeq<'_, T> {
+    fn z_len(&self) -> usize {
+        self.len().z_len() + self.z_len_remain()
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        let result = self.len().z_encode(w).and_then(|_| self.z_encode_remain(w));
+        z_encode_sized(self, result)
+    }
+
+    fn z_encode_vectored<'s>(&'s self, w: &mut ZVectoredWriter<'s, '_>) -> ZResult<()> {
+        let result = self
+            .len()
+            .z_encode(w)
+            .and_then(|_| self.z_encode_remain_vectored(w));
+        z_encode_sized(self, result)
+    }
+}
+
+impl<'a, T: ZStructDecode<'a>> ZStructDecode<'a> for ZSeq<'a, T> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
+        let len = <usize as ZStructDecode>::z_decode(r)?;
+
+        // Each element takes at least one byte.
+        if len > r.remaining() {
+            return Err(ZCodecError::CouldNotRead);
+        }
+
+        let mut probe = r.clone();
+        for _ in 0..len {
+            skip::<T, R>(&mut probe)?;
+        }
+
+        let bytes = r.read(r.remaining() - probe.remaining())?;
+
+        Ok(Self {
+            repr: Repr::Encoded { len, bytes },
+        })
+    }
+}
+
+impl<'a, T> From<&'a [T]> for ZSeq<'a, T> {
+    fn from(slice: &'a [T]) -> Self {
+        Self::new(slice)
+    }
+}
+
+impl<T> Clone for ZSeq<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ZSeq<'_, T> {}
+
+impl<T> Clone for Repr<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Repr<'_, T> {}
+
+impl<'a, T: ZStructDecode<'a> + Clone + PartialEq> PartialEq for ZSeq<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<'a, T: ZStructDecode<'a> + Clone + fmt::Debug> fmt::Debug for ZSeq<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+
+        for x in self.iter() {
+            match x {
+                Ok(x) => list.entry(&x),
+                Err(e) => list.entry(&e),
+            };
+        }
+
+        list.finish()
+    }
+}
+
+/// A sequence to encode whose elements are produced by an iterator, encoded like a `ZSeq`.
+///
+/// It is encode-only: a struct generic over its sequence type can be encoded with a `ZSeqFrom`
+/// and decoded with a `ZSeq`. The iterator is cloned to count and size the elements, so it
+/// should be cheap to clone, e.g. a `map` over a slice iterator.
+#[derive(Clone)]
+pub struct ZSeqFrom<I> {
+    iter: I,
+}
+
+impl<I> ZSeqFrom<I> {
+    pub const fn new(iter: I) -> Self {
+        Self { iter }
+    }
+}
+
+impl<I: Iterator + Clone> ZSeqFrom<I>
+where
+    I::Item: ZStructEncode,
+{
+    /// Length of the elements alone, as encoded with `#[zenoh(size = remain)]`.
+    pub fn z_len_remain(&self) -> usize {
+        self.iter.clone().map(|x| x.z_len()).sum()
+    }
+
+    /// Encodes the elements alone, as with `#[zenoh(size = remain)]`.
+    pub fn z_encode_remain<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        self.iter.clone().try_for_each(|x| x.z_encode(w))
+    }
+}
+
+impl<I: Iterator + Clone> ZStructEncode for ZSeqFrom<I>
+where
+    I::Item: ZStructEncode,
+{
+    fn z_len(&self) -> usize {
+        self.iter.clone().count().z_len() + self.z_len_remain()
+    }
+
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        let result = (self.iter.clone().count().z_encode(w)).and_then(|_| self.z_encode_remain(w));
+        z_encode_sized(self, result)
+    }
+}
+
+/// Iterator over the elements of a `ZSeq`.
+pub enum ZSeqIter<'a, T> {
+    Slice(slice::Iter<'a, T>),
+    Encoded { len: usize, bytes: &'a [u8] },
+}
+
+impl<'a, T: ZStructDecode<'a> + Clone> Iterator for ZSeqIter<'a, T> {
+    type Item = ZResult<T>;
+
+    fn next(&mut self) -> Option<ZResult<T>> {
+        match self {
+            Self::Slice(iter) => iter.next().cloned().map(Ok),
+            Self::Encoded { len, bytes } => {
+                if *len == 0 {
+                    return None;
+                }
+
+                // Boundaries were checked when the sequence was decoded, but the error is still
+                // reported rather than taken for the end of the sequence. No element follows it.
+                let x = T::z_decode(bytes);
+                *len = match x {
+                    Ok(_) => *len - 1,
+                    Err(_) => 0,
+                };
+
+                Some(x)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self {
+            Self::Slice(iter) => iter.len(),
+            Self::Encoded { len, .. } => *len,
+        };
+
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: ZStructDecode<'a> + Clone> ExactSizeIterator for ZSeqIter<'a, T> {}
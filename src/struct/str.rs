@@ -1,21 +1,19 @@
-use crate::{
-    ZCodecError, ZReader, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriter, ZWriterExt,
-};
+use crate::{ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriterExt};
 
 impl ZStructEncode for &'_ str {
     fn z_len(&self) -> usize {
         self.len()
     }
 
-    fn z_encode(&self, w: &mut ZWriter) -> ZResult<()> {
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
         w.write_exact(self.as_bytes())
     }
 }
 
 impl<'a> ZStructDecode<'a> for &'a str {
-    fn z_decode(r: &mut ZReader<'a>) -> ZResult<Self> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
         let bytes = r.read(r.remaining())?;
 
-        core::str::from_utf8(bytes).map_err(|_| ZCodecError::CouldNotParse)
+        core::str::from_utf8(bytes).map_err(|_| ZCodecError::InvalidUtf8)
     }
 }
@@ -1,24 +1,24 @@
-use crate::{ZReader, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriter, ZWriterExt};
+use crate::{ZCodecError, ZReaderExt, ZResult, ZStructDecode, ZStructEncode, ZWriterExt};
 
 impl ZStructEncode for u8 {
     fn z_len(&self) -> usize {
         1
     }
 
-    fn z_encode(&self, w: &mut ZWriter) -> ZResult<()> {
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
         w.write_u8(*self)
     }
 }
 
 impl<'a> ZStructDecode<'a> for u8 {
-    fn z_decode(reader: &mut ZReader<'a>) -> ZResult<Self> {
+    fn z_decode<R: ZReaderExt<'a>>(reader: &mut R) -> ZResult<Self> {
         reader.read_u8()
     }
 }
 
-const VLE_LEN_MAX: usize = vle_len(u64::MAX);
+pub(crate) const VLE_LEN_MAX: usize = vle_len(u64::MAX);
 
-const fn vle_len(x: u64) -> usize {
+pub(crate) const fn vle_len(x: u64) -> usize {
     const B1: u64 = u64::MAX << 7;
     const B2: u64 = u64::MAX << (7 * 2);
     const B3: u64 = u64::MAX << (7 * 3);
@@ -54,37 +54,44 @@ impl ZStructEncode for u64 {
         vle_len(*self)
     }
 
-    fn z_encode(&self, w: &mut ZWriter) -> ZResult<()> {
-        let mut x = *self;
-
-        w.write_slot(VLE_LEN_MAX, |buffer: &mut [u8]| {
-            let mut len = 0;
-
-            while (x & !0x7f_u64) != 0 {
-                unsafe {
-                    *buffer.get_unchecked_mut(len) = (x as u8) | 0x80_u8;
-                }
+    fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
+        w.write_slot(vle_len(*self), |buffer: &mut [u8]| unsafe {
+            vle_encode(*self, buffer)
+        })?;
 
-                len += 1;
-                x >>= 7;
-            }
+        Ok(())
+    }
+}
 
-            if len != VLE_LEN_MAX {
-                unsafe {
-                    *buffer.get_unchecked_mut(len) = x as u8;
-                }
-                len += 1;
-            }
+/// Writes `x` as a VLE at the start of `buffer` and returns the number of bytes written.
+///
+/// # Safety
+///
+/// `buffer` must be at least `vle_len(x)` bytes long.
+pub(crate) unsafe fn vle_encode(mut x: u64, buffer: &mut [u8]) -> usize {
+    let mut len = 0;
+
+    while (x & !0x7f_u64) != 0 {
+        unsafe {
+            *buffer.get_unchecked_mut(len) = (x as u8) | 0x80_u8;
+        }
 
-            len
-        })?;
+        len += 1;
+        x >>= 7;
+    }
 
-        Ok(())
+    if len != VLE_LEN_MAX {
+        unsafe {
+            *buffer.get_unchecked_mut(len) = x as u8;
+        }
+        len += 1;
     }
+
+    len
 }
 
 impl<'a> ZStructDecode<'a> for u64 {
-    fn z_decode(r: &mut ZReader<'a>) -> ZResult<Self> {
+    fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
         let mut b = r.read_u8()?;
 
         let mut v = 0;
@@ -96,6 +103,12 @@ impl<'a> ZStructDecode<'a> for u64 {
             i += 7;
         }
 
+        // With the `strict` feature, a VLE must use as few bytes as its value requires: a last
+        // byte of 0 could have been dropped.
+        if cfg!(feature = "strict") && b == 0 && i != 0 {
+            return Err(ZCodecError::VleOverflow);
+        }
+
         v |= (b as u64) << i;
 
         Ok(v)
@@ -110,16 +123,18 @@ macro_rules! zint {
                     vle_len(*self as u64)
                 }
 
-                fn z_encode(&self, w: &mut ZWriter) -> ZResult<()> {
+                fn z_encode<W: ZWriterExt>(&self, w: &mut W) -> ZResult<()> {
                     let v = *self as u64;
                     v.z_encode(w)
                 }
             }
 
+            // Values that do not fit are rejected instead of truncated, including `usize`
+            // lengths on 32-bit targets.
             impl<'a> ZStructDecode<'a> for $ty {
-                fn z_decode(r: &mut ZReader<'a>) -> ZResult<Self> {
+                fn z_decode<R: ZReaderExt<'a>>(r: &mut R) -> ZResult<Self> {
                     let v = <u64 as ZStructDecode>::z_decode(r)?;
-                    Ok(v as $ty)
+                    <$ty>::try_from(v).map_err(|_| ZCodecError::IntegerOutOfRange)
                 }
             }
         )*
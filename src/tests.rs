@@ -1,2 +1,20 @@
+#[cfg(feature = "bytes")]
+mod bytes;
+#[cfg(feature = "tokio")]
+mod codec;
+mod r#enum;
+mod error;
 mod ext;
+#[cfg(feature = "heapless")]
+mod heapless;
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "alloc")]
+mod owned;
+mod reader;
+mod seq;
+#[cfg(feature = "strict")]
+mod strict;
 mod r#struct;
+mod trace;
+mod writer;
@@ -0,0 +1,75 @@
+use bytes::{Bytes, BytesMut};
+
+use crate::{self as zenoh_codec};
+use crate::{ZBytesReader, ZStruct, ZStructDecode, ZStructEncode};
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "K|_:7")]
+struct ZBatched {
+    pub sn: u64,
+
+    #[zenoh(presence = header(K), size = prefixed)]
+    pub key: Option<bytes::Bytes>,
+
+    #[zenoh(size = remain)]
+    pub payload: bytes::Bytes,
+}
+
+#[test]
+fn test_bytes_mut_writer() {
+    let x = ZBatched {
+        sn: 42,
+        key: Some(Bytes::from_static(&[1; 200])),
+        payload: Bytes::from_static(&[2; 16]),
+    };
+
+    let mut data = BytesMut::new();
+    <_ as ZStructEncode>::z_encode(&x, &mut data).unwrap();
+    assert_eq!(data.len(), <_ as ZStructEncode>::z_len(&x));
+
+    let mut fixed = [0u8; 256];
+    let mut writer = fixed.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
+    assert_eq!(&fixed[..data.len()], &data[..]);
+}
+
+#[test]
+fn test_bytes_zero_copy_decode() {
+    let x = ZBatched {
+        sn: 7,
+        key: Some(Bytes::from_static(b"some/key")),
+        payload: Bytes::from_static(&[3; 64]),
+    };
+
+    let mut data = BytesMut::new();
+    <_ as ZStructEncode>::z_encode(&x, &mut data).unwrap();
+    let batch = data.freeze();
+
+    let decoded = <ZBatched as ZStructDecode>::z_decode(&mut ZBytesReader::new(&batch)).unwrap();
+    assert_eq!(decoded, x);
+
+    let range = batch.as_ptr_range();
+    assert!(range.contains(&decoded.payload.as_ptr()));
+    assert!(range.contains(&decoded.key.as_ref().unwrap().as_ptr()));
+
+    // The decoded payloads keep the batch alive.
+    drop(batch);
+    assert_eq!(decoded, x);
+}
+
+#[test]
+fn test_bytes_decode_from_slice_copies() {
+    let x = ZBatched {
+        sn: 7,
+        key: None,
+        payload: Bytes::from_static(&[4; 8]),
+    };
+
+    let mut data = [0u8; 32];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
+
+    let len = <_ as ZStructEncode>::z_len(&x);
+    let decoded = <ZBatched as ZStructDecode>::z_decode(&mut &data[..len]).unwrap();
+    assert_eq!(decoded, x);
+}
@@ -0,0 +1,90 @@
+use bytes::{Bytes, BytesMut};
+use futures::{SinkExt, StreamExt};
+use std::io;
+use tokio_util::codec::{Decoder, Encoder, FramedRead, FramedWrite};
+
+use crate::{self as zenoh_codec};
+use crate::{ZBatchCodec, ZStruct};
+
+#[derive(ZStruct, PartialEq, Debug, Clone)]
+struct ZBatch {
+    pub sn: u64,
+
+    #[zenoh(size = remain)]
+    pub payload: bytes::Bytes,
+}
+
+#[tokio::test]
+async fn test_codec_duplex() {
+    let batches = [
+        ZBatch {
+            sn: 1,
+            payload: Bytes::from_static(&[1; 300]),
+        },
+        ZBatch {
+            sn: 2,
+            payload: Bytes::new(),
+        },
+        ZBatch {
+            sn: u64::MAX,
+            payload: Bytes::from_static(b"hello"),
+        },
+    ];
+
+    let (client, server) = tokio::io::duplex(64);
+
+    let sent = batches.clone();
+    let send = tokio::spawn(async move {
+        let mut sink = FramedWrite::new(client, ZBatchCodec::<ZBatch>::new());
+        for batch in sent {
+            sink.send(batch).await.unwrap();
+        }
+    });
+
+    let received = FramedRead::new(server, ZBatchCodec::<ZBatch>::new())
+        .map(Result::unwrap)
+        .collect::<std::vec::Vec<_>>()
+        .await;
+
+    send.await.unwrap();
+    assert_eq!(received, batches);
+}
+
+#[test]
+fn test_codec_partial_frames() {
+    let x = ZBatch {
+        sn: 42,
+        payload: Bytes::from_static(&[7; 16]),
+    };
+
+    let mut codec = ZBatchCodec::<ZBatch>::new();
+    let mut encoded = BytesMut::new();
+    codec.encode(x.clone(), &mut encoded).unwrap();
+    assert_eq!(&encoded[..2], &(encoded.len() as u16 - 2).to_le_bytes());
+
+    let mut src = BytesMut::new();
+    for byte in &encoded[..encoded.len() - 1] {
+        src.extend_from_slice(&[*byte]);
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+    }
+
+    src.extend_from_slice(&encoded[encoded.len() - 1..]);
+    assert_eq!(codec.decode(&mut src).unwrap(), Some(x));
+    assert!(src.is_empty());
+}
+
+#[test]
+fn test_codec_batch_too_large() {
+    let x = ZBatch {
+        sn: 0,
+        payload: Bytes::from(std::vec![0; u16::MAX as usize]),
+    };
+
+    let mut dst = BytesMut::new();
+    let err = ZBatchCodec::<ZBatch>::new()
+        .encode(x, &mut dst)
+        .unwrap_err();
+
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(dst.is_empty());
+}
@@ -0,0 +1,142 @@
+use crate::{self as zenoh_codec};
+use crate::{ZCodecError, ZEnum, ZReaderExt, ZStruct, ZStructDecode, ZStructEncode};
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "_:3|ID:5=0x1")]
+struct ZPush<'a> {
+    pub sn: u32,
+    #[zenoh(size = remain)]
+    pub payload: &'a [u8],
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "_:2|I|ID:5=0x2")]
+struct ZDeclare {
+    #[zenoh(presence = header(I))]
+    pub id: Option<u32>,
+}
+
+#[derive(ZEnum, PartialEq, Debug)]
+enum ZNetworkMessage<'a> {
+    Push(ZPush<'a>),
+    Declare(ZDeclare),
+}
+
+#[derive(ZEnum, PartialEq, Debug)]
+enum ZLenientMessage<'b> {
+    Declare(ZDeclare),
+    #[zenoh(unknown)]
+    Unknown(&'b [u8]),
+}
+
+macro_rules! roundtrip {
+    ($ty:ty, $value:expr) => {{
+        let mut data = [0u8; 64];
+        let mut writer = data.as_mut_slice();
+
+        let len = <_ as ZStructEncode>::z_len(&$value);
+        <_ as ZStructEncode>::z_encode(&$value, &mut writer).unwrap();
+
+        let mut reader = data.as_slice();
+        let decoded = <$ty as ZStructDecode>::z_decode(&mut reader.sub(len).unwrap()).unwrap();
+
+        assert_eq!(decoded, $value);
+    }};
+}
+
+#[test]
+fn test_zenum_dispatch() {
+    let payload = [1, 2, 3];
+    let push = ZNetworkMessage::Push(ZPush {
+        sn: 7,
+        payload: &payload,
+    });
+    let declare = ZNetworkMessage::Declare(ZDeclare { id: Some(3) });
+
+    assert_eq!(
+        <_ as ZStructEncode>::z_len(&push),
+        <_ as ZStructEncode>::z_len(&ZPush {
+            sn: 7,
+            payload: &payload,
+        })
+    );
+
+    roundtrip!(ZNetworkMessage, push);
+    roundtrip!(ZNetworkMessage, declare);
+    roundtrip!(
+        ZNetworkMessage,
+        ZNetworkMessage::Declare(ZDeclare { id: None })
+    );
+}
+
+#[test]
+fn test_zenum_unknown() {
+    let data = [0x1f, 0xaa, 0xbb];
+
+    assert_eq!(
+        <ZNetworkMessage as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(ZCodecError::UnknownMessage { header: 0x1f })
+    );
+    assert_eq!(
+        <ZLenientMessage as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Ok(ZLenientMessage::Unknown(&data))
+    );
+
+    roundtrip!(ZLenientMessage, ZLenientMessage::Unknown(&data));
+    roundtrip!(
+        ZLenientMessage,
+        ZLenientMessage::Declare(ZDeclare { id: Some(1) })
+    );
+}
+
+#[derive(ZEnum, Clone, Copy, PartialEq, Debug)]
+#[repr(u8)]
+enum ZPriority {
+    Control = 0,
+    RealTime = 1,
+    Data = 5,
+    Background = 7,
+}
+
+#[derive(ZEnum, Clone, Copy, PartialEq, Debug)]
+#[repr(u8)]
+enum ZWhatAmI {
+    Router = 0b001,
+    Peer = 0b010,
+    Client = 0b100,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "P:3|_:5")]
+struct ZQoS {
+    #[zenoh(header = P)]
+    pub priority: ZPriority,
+    pub whatami: ZWhatAmI,
+}
+
+#[test]
+fn test_zenum_fieldless() {
+    assert_eq!(ZPriority::Z_BITS, 3);
+    assert_eq!(ZWhatAmI::Z_BITS, 3);
+    assert_eq!(u8::from(ZPriority::Data), 5);
+    assert_eq!(ZPriority::try_from(7), Ok(ZPriority::Background));
+    assert_eq!(ZPriority::try_from(2), Err(ZCodecError::IntegerOutOfRange));
+
+    let x = ZQoS {
+        priority: ZPriority::Data,
+        whatami: ZWhatAmI::Client,
+    };
+
+    let mut data = [0u8; 2];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
+    assert_eq!(data, [0b1010_0000, 0b100]);
+
+    roundtrip!(ZQoS, x);
+
+    data[1] = 3;
+    assert_eq!(
+        <ZQoS as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(ZCodecError::IntegerOutOfRange)
+    );
+}
@@ -0,0 +1,46 @@
+use crate::ZCodecError;
+
+#[test]
+fn test_error_codes() {
+    assert_eq!(ZCodecError::CouldNotRead.code(), 0);
+    assert_eq!(ZCodecError::FieldExceedsReservedSize.code(), 3);
+    assert_eq!(ZCodecError::UnknownMandatoryExtension { id: 0x7 }.code(), 4);
+    assert_eq!(ZCodecError::BufferTooSmall { required: 300 }.code(), 9);
+    assert_eq!(ZCodecError::TrailingBytes.code(), 10);
+    assert_eq!(ZCodecError::UnknownMessage { header: 0x1f }.code(), 11);
+    assert_eq!(ZCodecError::InvalidExtKind.code(), 12);
+    assert_eq!(ZCodecError::Io.code(), 13);
+
+    // The data carried by some variants makes the error two words wide.
+    assert_eq!(
+        core::mem::size_of::<ZCodecError>(),
+        2 * core::mem::size_of::<usize>()
+    );
+}
+
+#[test]
+fn test_error_ext_kind() {
+    assert_eq!(
+        crate::ZExtKind::try_from(0b0110_0000),
+        Err(ZCodecError::InvalidExtKind)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_error_display() {
+    use alloc::string::ToString;
+
+    assert_eq!(
+        ZCodecError::UnknownMandatoryExtension { id: 0x7 }.to_string(),
+        "unknown mandatory extension 0x7"
+    );
+    assert_eq!(
+        ZCodecError::BufferTooSmall { required: 300 }.to_string(),
+        "buffer too small, 300 bytes required"
+    );
+    assert_eq!(
+        ZCodecError::UnknownMessage { header: 0x1f }.to_string(),
+        "unknown message with header 0x1f"
+    );
+}
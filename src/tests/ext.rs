@@ -10,6 +10,28 @@ pub struct ZExtCounter {
     pub counter: u64,
 }
 
+#[derive(ZExt, PartialEq, Debug)]
+pub struct ZExtPriority {
+    pub priority: u16,
+}
+
+#[derive(ZExt, PartialEq, Debug)]
+pub struct ZExtDelta {
+    pub delta: i32,
+}
+
+#[derive(ZExt, PartialEq, Debug)]
+pub struct ZExtBatchLen {
+    #[zenoh(encoding = le)]
+    pub len: u16,
+}
+
+#[derive(ZExt, PartialEq, Debug)]
+pub struct ZExtQoS(pub u8);
+
+#[derive(ZExt, PartialEq, Debug)]
+pub struct ZExtExpress;
+
 #[derive(ZExt, PartialEq, Debug)]
 pub struct ZExtData<'a> {
     #[zenoh(size = prefixed)]
@@ -23,6 +45,13 @@ pub struct ZExtInfo<'a> {
     pub name: &'a str,
 }
 
+#[derive(ZExt, PartialEq, Debug)]
+pub struct ZExtTagged<'b, T> {
+    pub tag: T,
+    #[zenoh(size = remain)]
+    pub name: &'b str,
+}
+
 #[derive(ZExt, PartialEq, Debug)]
 #[zenoh(header = "Z|E|_:6")]
 pub struct ZExtHeader<'a> {
@@ -117,7 +146,7 @@ pub struct ZMsgComplexOption<'a> {
 macro_rules! roundtrip {
     ($ty:ty, $value:expr) => {{
         let mut data = [0u8; 256];
-        let mut writer = &mut data.as_mut_slice();
+        let mut writer = data.as_mut_slice();
 
         let len = <_ as ZStructEncode>::z_len(&$value);
         <_ as ZStructEncode>::z_encode(&$value, &mut writer).unwrap();
@@ -133,9 +162,14 @@ macro_rules! roundtrip {
 fn test_zext_kinds() {
     assert_eq!(ZExtEmpty::KIND, ZExtKind::Unit);
     assert_eq!(ZExtCounter::KIND, ZExtKind::U64);
+    assert_eq!(ZExtDelta::KIND, ZExtKind::U64);
+    assert_eq!(ZExtBatchLen::KIND, ZExtKind::ZStruct);
+    assert_eq!(ZExtQoS::KIND, ZExtKind::U64);
+    assert_eq!(ZExtExpress::KIND, ZExtKind::Unit);
     assert_eq!(ZExtData::KIND, ZExtKind::ZStruct);
     assert_eq!(ZExtInfo::KIND, ZExtKind::ZStruct);
     assert_eq!(ZExtHeader::KIND, ZExtKind::ZStruct);
+    assert_eq!(ZExtTagged::<u8>::KIND, ZExtKind::ZStruct);
 }
 
 #[test]
@@ -212,3 +246,56 @@ fn test_zmsg_partial_exts() {
     };
     roundtrip!(ZMsgComplexOption, msg);
 }
+
+#[test]
+fn test_zext_u64_out_of_range() {
+    let mut data = [0u8; 16];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&ZExtCounter { counter: 70000 }, &mut writer).unwrap();
+
+    assert_eq!(
+        <ZExtPriority as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(crate::ZCodecError::IntegerOutOfRange)
+    );
+
+    roundtrip!(ZExtPriority, ZExtPriority { priority: u16::MAX });
+}
+
+#[test]
+fn test_zext_signed() {
+    assert_eq!(<_ as ZStructEncode>::z_len(&ZExtDelta { delta: -1 }), 1);
+
+    roundtrip!(ZExtDelta, ZExtDelta { delta: -1 });
+    roundtrip!(ZExtDelta, ZExtDelta { delta: i32::MIN });
+}
+
+#[test]
+fn test_zext_fixed() {
+    assert_eq!(<_ as ZStructEncode>::z_len(&ZExtBatchLen { len: 1 }), 2);
+
+    roundtrip!(ZExtBatchLen, ZExtBatchLen { len: u16::MAX });
+}
+
+#[test]
+fn test_zext_tuple() {
+    roundtrip!(ZExtQoS, ZExtQoS(5));
+    roundtrip!(ZExtExpress, ZExtExpress);
+}
+
+#[test]
+fn test_zext_generic() {
+    roundtrip!(
+        ZExtTagged<u8>,
+        ZExtTagged {
+            tag: 3u8,
+            name: "t"
+        }
+    );
+    roundtrip!(
+        ZExtTagged<ZExtData>,
+        ZExtTagged {
+            tag: ZExtData { bytes: &[1, 2] },
+            name: "nested",
+        }
+    );
+}
@@ -0,0 +1,80 @@
+use heapless::{String, Vec};
+
+use crate::{self as zenoh_codec};
+use crate::{ZCodecError, ZStruct, ZStructDecode, ZStructEncode};
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "K|_:7")]
+struct ZFirmware {
+    pub sn: u32,
+
+    #[zenoh(presence = header(K), size = prefixed)]
+    pub keyexpr: Option<String<16>>,
+
+    #[zenoh(size = remain)]
+    pub payload: Vec<u8, 8>,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "K|_:7")]
+struct ZBorrowed<'a> {
+    pub sn: u32,
+
+    #[zenoh(presence = header(K), size = prefixed)]
+    pub keyexpr: Option<&'a str>,
+
+    #[zenoh(size = remain)]
+    pub payload: &'a [u8],
+}
+
+fn decode(x: &ZBorrowed) -> Result<ZFirmware, ZCodecError> {
+    let mut data = [0u8; 64];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(x, &mut writer).unwrap();
+
+    // The decoded value does not borrow from `data`, it outlives it.
+    let len = <_ as ZStructEncode>::z_len(x);
+    <ZFirmware as ZStructDecode>::z_decode(&mut &data[..len])
+}
+
+#[test]
+fn test_heapless_fields() {
+    let decoded = decode(&ZBorrowed {
+        sn: 7,
+        keyexpr: Some("key/expr"),
+        payload: &[1, 2, 3],
+    })
+    .unwrap();
+
+    assert_eq!(decoded.sn, 7);
+    assert_eq!(decoded.keyexpr.as_deref(), Some("key/expr"));
+    assert_eq!(decoded.payload.as_slice(), &[1, 2, 3]);
+
+    let mut data = [0u8; 64];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&decoded, &mut writer).unwrap();
+
+    let len = <_ as ZStructEncode>::z_len(&decoded);
+    let mut reader = &data[..len];
+    assert_eq!(
+        <ZFirmware as ZStructDecode>::z_decode(&mut reader),
+        Ok(decoded)
+    );
+}
+
+#[test]
+fn test_heapless_exceeds_capacity() {
+    let string = decode(&ZBorrowed {
+        sn: 7,
+        keyexpr: Some("a/very/long/key/expr"),
+        payload: &[1, 2, 3],
+    });
+    assert_eq!(string, Err(ZCodecError::FieldExceedsReservedSize));
+
+    let vec = decode(&ZBorrowed {
+        sn: 7,
+        keyexpr: Some("key/expr"),
+        payload: &[0; 9],
+    });
+    assert_eq!(vec, Err(ZCodecError::FieldExceedsReservedSize));
+}
@@ -0,0 +1,122 @@
+use std::{io, string::ToString, vec::Vec};
+
+use crate::{self as zenoh_codec};
+use crate::{FRAME_LEN_MAX, ZCodecError, ZStruct, decode_from_reader, encode_to_writer};
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZStreamed<'a> {
+    pub sn: u64,
+    #[zenoh(size = remain)]
+    pub payload: &'a [u8],
+}
+
+#[test]
+fn test_io_back_to_back() {
+    let payload = [7u8; 200];
+    let first = ZStreamed {
+        sn: 1,
+        payload: &payload,
+    };
+    let second = ZStreamed {
+        sn: 2,
+        payload: &[1, 2, 3],
+    };
+
+    let mut stream = Vec::new();
+    encode_to_writer(&first, &mut stream).unwrap();
+    encode_to_writer(&second, &mut stream).unwrap();
+
+    let mut reader = io::Cursor::new(stream);
+    let mut buf = Vec::new();
+
+    let decoded = decode_from_reader::<ZStreamed>(&mut reader, &mut buf).unwrap();
+    assert_eq!(decoded, first);
+    let decoded = decode_from_reader::<ZStreamed>(&mut reader, &mut buf).unwrap();
+    assert_eq!(decoded, second);
+
+    let err = decode_from_reader::<ZStreamed>(&mut reader, &mut buf).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_io_truncated() {
+    let mut stream = Vec::new();
+    encode_to_writer(
+        &ZStreamed {
+            sn: 1,
+            payload: &[1, 2, 3],
+        },
+        &mut stream,
+    )
+    .unwrap();
+    stream.pop();
+
+    let mut buf = Vec::new();
+    let err = decode_from_reader::<ZStreamed>(&mut stream.as_slice(), &mut buf).unwrap_err();
+    assert_eq!(ZCodecError::from(err), ZCodecError::CouldNotRead);
+}
+
+#[test]
+fn test_io_frame_too_long() {
+    // A peer announcing an `i64::MAX` long message.
+    let stream = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f];
+
+    let mut buf = Vec::new();
+    let err = decode_from_reader::<ZStreamed>(&mut stream.as_slice(), &mut buf).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(buf.capacity() < FRAME_LEN_MAX);
+
+    // Announcing an accepted length without sending it does not allocate it either.
+    let stream = [0x80, 0x80, 0x80, 0x04, 1, 2, 3];
+    let err = decode_from_reader::<ZStreamed>(&mut stream.as_slice(), &mut buf).unwrap_err();
+    assert_eq!(ZCodecError::from(err), ZCodecError::CouldNotRead);
+    assert!(buf.capacity() < FRAME_LEN_MAX);
+
+    let payload = std::vec![0u8; FRAME_LEN_MAX];
+    let x = ZStreamed {
+        sn: 1,
+        payload: &payload,
+    };
+    let err = encode_to_writer(&x, &mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_io_error_mapping() {
+    for err in [
+        ZCodecError::CouldNotRead,
+        ZCodecError::CouldNotWrite,
+        ZCodecError::CouldNotParse,
+        ZCodecError::FieldExceedsReservedSize,
+        ZCodecError::UnknownMandatoryExtension { id: 0x7 },
+        ZCodecError::InvalidUtf8,
+        ZCodecError::VleOverflow,
+        ZCodecError::IntegerOutOfRange,
+        ZCodecError::HeaderMismatch,
+        ZCodecError::BufferTooSmall { required: 3 },
+        ZCodecError::TrailingBytes,
+        ZCodecError::UnknownMessage { header: 0x1f },
+        ZCodecError::InvalidExtKind,
+        ZCodecError::Io,
+    ] {
+        assert_eq!(ZCodecError::from(io::Error::from(err)), err);
+    }
+
+    let err = io::Error::from(ZCodecError::InvalidUtf8);
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "invalid UTF-8");
+
+    let err = io::Error::from(io::ErrorKind::WriteZero);
+    assert_eq!(ZCodecError::from(err), ZCodecError::CouldNotWrite);
+
+    let err = io::Error::from(io::ErrorKind::UnexpectedEof);
+    assert_eq!(ZCodecError::from(err), ZCodecError::CouldNotRead);
+
+    for kind in [
+        io::ErrorKind::BrokenPipe,
+        io::ErrorKind::ConnectionReset,
+        io::ErrorKind::InvalidInput,
+    ] {
+        assert_eq!(ZCodecError::from(io::Error::from(kind)), ZCodecError::Io);
+    }
+}
@@ -0,0 +1,281 @@
+use alloc::{borrow::Cow, boxed::Box, string::String, vec, vec::Vec};
+
+use crate::{self as zenoh_codec};
+use crate::{
+    ZChunkedReader, ZExt, ZReaderExt, ZSegment, ZStruct, ZStructDecode, ZStructEncode,
+    ZVectoredWriter,
+};
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct Inner {
+    pub a: u32,
+    #[zenoh(size = prefixed)]
+    pub name: String,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "S:4|V|_:3")]
+struct ZOwned<'a> {
+    #[zenoh(size = header(S))]
+    pub bytes: Vec<u8>,
+
+    #[zenoh(presence = header(V), size = prefixed)]
+    pub maybe_string: Option<String>,
+
+    #[zenoh(size = prefixed)]
+    pub boxed: Box<Inner>,
+
+    #[zenoh(size = prefixed)]
+    pub cow_bytes: Cow<'a, [u8]>,
+
+    #[zenoh(size = remain)]
+    pub cow_str: Cow<'a, str>,
+}
+
+macro_rules! roundtrip {
+    ($ty:ty, $value:expr) => {{
+        let mut data = [0u8; 256];
+        let mut writer = data.as_mut_slice();
+
+        let len = <_ as ZStructEncode>::z_len(&$value);
+        <_ as ZStructEncode>::z_encode(&$value, &mut writer).unwrap();
+
+        let mut reader = data.as_slice();
+        let decoded = <$ty as ZStructDecode>::z_decode(&mut reader.sub(len).unwrap()).unwrap();
+
+        assert_eq!(decoded, $value);
+    }};
+}
+
+fn zowned() -> ZOwned<'static> {
+    ZOwned {
+        bytes: vec![1, 2, 3],
+        maybe_string: Some(String::from("owned")),
+        boxed: Box::new(Inner {
+            a: 42,
+            name: String::from("inner"),
+        }),
+        cow_bytes: Cow::Owned(vec![4, 5]),
+        cow_str: Cow::Borrowed("cow"),
+    }
+}
+
+#[test]
+fn test_owned_fields() {
+    roundtrip!(ZOwned, zowned());
+
+    let x = ZOwned {
+        maybe_string: None,
+        ..zowned()
+    };
+    roundtrip!(ZOwned, x);
+}
+
+#[test]
+fn test_owned_outlives_buffer() {
+    let x = Inner {
+        a: 7,
+        name: String::from("outlives"),
+    };
+
+    let decoded = {
+        let mut data = Vec::new();
+        <_ as ZStructEncode>::z_encode(&x, &mut data).unwrap();
+        <Inner as ZStructDecode>::z_decode(&mut data.as_slice()).unwrap()
+    };
+
+    assert_eq!(decoded, x);
+}
+
+#[test]
+fn test_owned_across_chunks() {
+    let x = Inner {
+        a: 7,
+        name: String::from("split/across/chunks"),
+    };
+
+    let mut data = Vec::new();
+    <_ as ZStructEncode>::z_encode(&x, &mut data).unwrap();
+
+    let (left, right) = data.split_at(8);
+    let chunks = [left, right];
+    let decoded = <Inner as ZStructDecode>::z_decode(&mut ZChunkedReader::new(&chunks)).unwrap();
+
+    assert_eq!(decoded, x);
+}
+
+#[test]
+fn test_cow_across_chunks() {
+    let x = zowned();
+
+    let mut data = Vec::new();
+    <_ as ZStructEncode>::z_encode(&x, &mut data).unwrap();
+
+    // Split inside `cow_bytes`, `cow_str` stays in the last chunk.
+    let (left, right) = data.split_at(data.len() - 4);
+    let chunks = [left, right];
+    let decoded = <ZOwned as ZStructDecode>::z_decode(&mut ZChunkedReader::new(&chunks)).unwrap();
+
+    assert_eq!(decoded, x);
+    assert!(matches!(decoded.cow_bytes, Cow::Owned(_)));
+    assert!(matches!(decoded.cow_str, Cow::Borrowed(_)));
+}
+
+#[derive(ZExt, PartialEq, Debug)]
+#[zenoh(owned = ZExtNameOwned)]
+struct ZExtName<'a> {
+    pub id: u16,
+    #[zenoh(size = remain)]
+    pub name: &'a str,
+}
+
+#[derive(ZExt, PartialEq, Debug, Clone)]
+#[zenoh(owned = Self)]
+struct ZExtSn {
+    pub sn: u32,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "Z|_:7", owned = ZMsgOwned)]
+struct ZMsg<'a> {
+    #[zenoh(size = prefixed)]
+    pub keyexpr: &'a str,
+
+    #[zenoh(ext = 0x1)]
+    pub ext_name: Option<ZExtName<'a>>,
+    #[zenoh(ext = 0x2)]
+    pub ext_sn: Option<ZExtSn>,
+
+    #[zenoh(size = remain)]
+    pub payload: &'a [u8],
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(owned = ZKeyOwned)]
+struct ZKey<'a>(u16, #[zenoh(size = remain)] &'a str);
+
+fn assert_static<T: Send + 'static>(_: &T) {}
+
+#[test]
+fn test_owned_companion() {
+    let payload = [1, 2, 3, 4];
+    let x = ZMsg {
+        keyexpr: "key/expr",
+        ext_name: Some(ZExtName { id: 3, name: "ext" }),
+        ext_sn: Some(ZExtSn { sn: 99 }),
+        payload: &payload,
+    };
+
+    let owned = {
+        let mut data = Vec::new();
+        <_ as ZStructEncode>::z_encode(&x, &mut data).unwrap();
+        <ZMsg as ZStructDecode>::z_decode(&mut data.as_slice())
+            .unwrap()
+            .into_owned()
+    };
+
+    assert_static(&owned);
+    assert_eq!(owned.keyexpr, "key/expr");
+    assert_eq!(owned.payload, vec![1, 2, 3, 4]);
+    assert_eq!(owned.ext_name.as_ref().unwrap().name, "ext");
+    assert_eq!(owned.as_borrowed(), x);
+
+    roundtrip!(ZMsgOwned, owned);
+}
+
+#[test]
+fn test_owned_companion_ext() {
+    assert_eq!(<ZExtNameOwned as ZExt>::KIND, crate::ZExtKind::ZStruct);
+
+    let x = ZExtNameOwned {
+        id: 12,
+        name: String::from("name"),
+    };
+
+    roundtrip!(ZExtNameOwned, x);
+}
+
+#[test]
+fn test_owned_companion_tuple() {
+    let owned = ZKey(1, "key").into_owned();
+
+    assert_static(&owned);
+    assert_eq!(owned, ZKeyOwned(1, String::from("key")));
+
+    roundtrip!(ZKeyOwned, owned);
+}
+
+const NO_NAME: ZExtName<'static> = ZExtName { id: 0, name: "" };
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "Z|_:7", owned = ZDefaultsOwned)]
+struct ZDefaults<'a> {
+    #[zenoh(ext = 0x1, default = NO_NAME)]
+    pub ext_name: ZExtName<'a>,
+
+    #[zenoh(size = remain)]
+    pub payload: &'a [u8],
+}
+
+#[test]
+fn test_owned_companion_encodes_in_place() {
+    let owned = ZDefaultsOwned {
+        ext_name: ZExtNameOwned {
+            id: 0,
+            name: String::new(),
+        },
+        payload: vec![7; 64],
+    };
+    roundtrip!(ZDefaultsOwned, owned);
+
+    let owned = ZDefaultsOwned {
+        ext_name: ZExtNameOwned {
+            id: 1,
+            name: String::from("ext"),
+        },
+        ..owned
+    };
+    roundtrip!(ZDefaultsOwned, owned);
+
+    // The payload is borrowed from the companion itself, not from a borrowed copy of it.
+    let mut scratch = [0u8; 32];
+    let mut segments = [ZSegment::default(); 4];
+    let mut writer = ZVectoredWriter::new(&mut scratch, &mut segments);
+    <_ as ZStructEncode>::z_encode_vectored(&owned, &mut writer).unwrap();
+
+    assert_eq!(writer.len(), <_ as ZStructEncode>::z_len(&owned));
+    assert!(
+        writer
+            .segments()
+            .contains(&ZSegment::Borrowed(owned.payload.as_slice()))
+    );
+}
+
+#[test]
+fn test_owned_companion_chunked() {
+    let payload = [7; 16];
+    let x = ZDefaults {
+        ext_name: ZExtName { id: 1, name: "ext" },
+        payload: &payload,
+    };
+
+    let mut data = Vec::new();
+    <_ as ZStructEncode>::z_encode(&x, &mut data).unwrap();
+
+    // Both the extension name and the payload span chunks.
+    let split = data.len() - payload.len() / 2;
+    let chunks: [&[u8]; 3] = [
+        &data[..split - 10],
+        &data[split - 10..split],
+        &data[split..],
+    ];
+
+    let mut reader = ZChunkedReader::new(&chunks);
+    assert!(<ZDefaults as ZStructDecode>::z_decode(&mut reader).is_err());
+
+    let mut reader = ZChunkedReader::new(&chunks);
+    let owned = <ZDefaultsOwned as ZStructDecode>::z_decode(&mut reader).unwrap();
+
+    assert_eq!(owned.as_borrowed(), x);
+    assert_eq!(reader.remaining(), 0);
+}
@@ -0,0 +1,149 @@
+use crate::{self as zenoh_codec};
+use crate::{ZChunkedReader, ZCodecError, ZReaderExt, ZStruct, ZStructDecode, ZStructEncode};
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "K|_:7")]
+struct ZChunked<'a> {
+    pub sn: u32,
+
+    #[zenoh(presence = header(K), size = prefixed)]
+    pub keyexpr: Option<&'a str>,
+
+    pub array: [u8; 6],
+
+    #[zenoh(size = remain)]
+    pub payload: &'a [u8],
+}
+
+fn encode(x: &ZChunked, data: &mut [u8]) -> usize {
+    let mut writer = &mut data[..];
+    let len = <_ as ZStructEncode>::z_len(x);
+    <_ as ZStructEncode>::z_encode(x, &mut writer).unwrap();
+    len
+}
+
+#[test]
+fn test_chunked_primitives() {
+    let chunks: [&[u8]; 4] = [&[0x01], &[], &[0x80, 0x01], &[0xAA, 0xBB, 0xCC]];
+    let mut reader = ZChunkedReader::new(&chunks);
+
+    assert_eq!(reader.remaining(), 6);
+    assert_eq!(reader.peek_u8().unwrap(), 0x01);
+    assert_eq!(<u8 as ZStructDecode>::z_decode(&mut reader).unwrap(), 0x01);
+    assert_eq!(<u64 as ZStructDecode>::z_decode(&mut reader).unwrap(), 128);
+
+    let mut sub = reader.sub(2).unwrap();
+    assert_eq!(reader.remaining(), 1);
+    assert_eq!(sub.read(2).unwrap(), &[0xAA, 0xBB]);
+    assert_eq!(sub.read_u8(), Err(ZCodecError::CouldNotRead));
+    assert_eq!(reader.read_u8().unwrap(), 0xCC);
+}
+
+#[test]
+fn test_chunked_split_everywhere() {
+    let x = ZChunked {
+        sn: 1234,
+        keyexpr: Some("key/expr"),
+        array: [1, 2, 3, 4, 5, 6],
+        payload: &[9, 8, 7],
+    };
+
+    let mut data = [0u8; 64];
+    let len = encode(&x, &mut data);
+    let data = &data[..len];
+
+    // Split points that do not cut through the borrowed `keyexpr` or `payload` fields.
+    let keyexpr = 1 + 2 + 1..1 + 2 + 1 + 8;
+    let payload = len - 3..len;
+
+    for at in 0..=len {
+        if keyexpr.contains(&at) && at != keyexpr.start
+            || payload.contains(&at) && at != payload.start
+        {
+            continue;
+        }
+
+        let (left, right) = data.split_at(at);
+        let chunks = [left, right];
+        let mut reader = ZChunkedReader::new(&chunks);
+
+        let decoded = <ZChunked as ZStructDecode>::z_decode(&mut reader).unwrap();
+        assert_eq!(decoded, x);
+    }
+}
+
+#[test]
+fn test_chunked_borrow_across_chunks() {
+    let x = ZChunked {
+        sn: 1,
+        keyexpr: None,
+        array: [0; 6],
+        payload: &[1, 2, 3, 4],
+    };
+
+    let mut data = [0u8; 64];
+    let len = encode(&x, &mut data);
+
+    let (left, right) = data[..len].split_at(len - 2);
+    let chunks = [left, right];
+    let mut reader = ZChunkedReader::new(&chunks);
+
+    assert_eq!(
+        <ZChunked as ZStructDecode>::z_decode(&mut reader),
+        Err(ZCodecError::CouldNotRead)
+    );
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZFixedChunked {
+    #[zenoh(encoding = be)]
+    pub id: u32,
+    #[zenoh(encoding = le)]
+    pub delta: i64,
+}
+
+#[test]
+fn test_chunked_fixed_encoding() {
+    let x = ZFixedChunked {
+        id: 0x0102_0304,
+        delta: -2,
+    };
+
+    let mut data = [0u8; 12];
+    <_ as ZStructEncode>::z_encode(&x, &mut data.as_mut_slice()).unwrap();
+
+    for at in 0..=data.len() {
+        let (left, right) = data.split_at(at);
+        let chunks = [left, right];
+        let mut reader = ZChunkedReader::new(&chunks);
+
+        let decoded = <ZFixedChunked as ZStructDecode>::z_decode(&mut reader).unwrap();
+        assert_eq!(decoded, x);
+    }
+
+    let chunks: [&[u8]; 2] = [&data[..2], &data[2..10]];
+    assert_eq!(
+        <ZFixedChunked as ZStructDecode>::z_decode(&mut ZChunkedReader::new(&chunks)),
+        Err(ZCodecError::CouldNotRead)
+    );
+}
+
+#[test]
+fn test_chunked_float() {
+    let mut data = [0u8; 8];
+    <_ as ZStructEncode>::z_encode(&-1.5f64, &mut data.as_mut_slice()).unwrap();
+
+    for at in 0..=data.len() {
+        let (left, right) = data.split_at(at);
+        let chunks = [left, right];
+        let mut reader = ZChunkedReader::new(&chunks);
+
+        assert_eq!(<f64 as ZStructDecode>::z_decode(&mut reader).unwrap(), -1.5);
+    }
+
+    let chunks: [&[u8]; 2] = [&data[..3], &data[3..7]];
+    assert_eq!(
+        <f64 as ZStructDecode>::z_decode(&mut ZChunkedReader::new(&chunks)),
+        Err(ZCodecError::CouldNotRead)
+    );
+}
@@ -0,0 +1,205 @@
+use crate::{self as zenoh_codec};
+use crate::{
+    ZCodecError, ZSegment, ZSeq, ZSeqFrom, ZStruct, ZStructDecode, ZStructEncode, ZVectoredWriter,
+};
+
+#[derive(ZStruct, PartialEq, Debug, Clone)]
+struct ZLocator<'a> {
+    pub port: u16,
+    #[zenoh(size = prefixed)]
+    pub addr: &'a str,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZHello<'a> {
+    pub locators: ZSeq<'a, ZLocator<'a>>,
+    pub sn: u32,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZFrame<'a> {
+    pub sn: u32,
+    #[zenoh(size = remain)]
+    pub messages: ZSeq<'a, ZLocator<'a>>,
+}
+
+const LOCATORS: [ZLocator<'static>; 3] = [
+    ZLocator {
+        port: 7447,
+        addr: "127.0.0.1",
+    },
+    ZLocator {
+        port: 7448,
+        addr: "::1",
+    },
+    ZLocator { port: 80, addr: "" },
+];
+
+#[test]
+fn test_zseq_count() {
+    let x = ZHello {
+        locators: ZSeq::new(&LOCATORS),
+        sn: 9,
+    };
+
+    let mut data = [0u8; 64];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
+    let len = <_ as ZStructEncode>::z_len(&x);
+    assert_eq!(data[0], 3);
+
+    let decoded = <ZHello as ZStructDecode>::z_decode(&mut &data[..len]).unwrap();
+    assert_eq!(decoded, x);
+    assert_eq!(decoded.locators.len(), 3);
+    assert!(decoded.locators.iter().eq(LOCATORS.map(Ok)));
+
+    // A decoded sequence is encoded back from its bytes.
+    let mut copy = [0u8; 64];
+    let mut writer = copy.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&decoded, &mut writer).unwrap();
+    assert_eq!(data, copy);
+
+    // The last element is cut.
+    assert_eq!(
+        <ZHello as ZStructDecode>::z_decode(&mut &data[..len - 3]),
+        Err(ZCodecError::CouldNotRead)
+    );
+}
+
+#[test]
+fn test_zseq_remain() {
+    let x = ZFrame {
+        sn: 1,
+        messages: ZSeq::new(&LOCATORS),
+    };
+
+    let mut data = [0u8; 64];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
+    let len = <_ as ZStructEncode>::z_len(&x);
+
+    let decoded = <ZFrame as ZStructDecode>::z_decode(&mut &data[..len]).unwrap();
+    assert_eq!(decoded, x);
+    assert_eq!(decoded.messages.len(), 3);
+
+    let empty = <ZFrame as ZStructDecode>::z_decode(&mut &data[..1]).unwrap();
+    assert!(empty.messages.is_empty());
+
+    assert!(<ZFrame as ZStructDecode>::z_decode(&mut &data[..len - 1]).is_err());
+}
+
+#[test]
+fn test_zseq_vectored() {
+    let frame = ZFrame {
+        sn: 3,
+        messages: ZSeq::new(&LOCATORS),
+    };
+
+    let mut data = [0u8; 64];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&frame, &mut writer).unwrap();
+    let len = <_ as ZStructEncode>::z_len(&frame);
+    let decoded = <ZFrame as ZStructDecode>::z_decode(&mut &data[..len]).unwrap();
+
+    let mut scratch = [0u8; 8];
+    let mut segments = [ZSegment::default(); 4];
+    let mut writer = ZVectoredWriter::new(&mut scratch, &mut segments);
+    <_ as ZStructEncode>::z_encode_vectored(&decoded, &mut writer).unwrap();
+    assert_eq!(writer.len(), len);
+
+    // The encoded elements are borrowed as a single segment.
+    assert!(matches!(
+        writer.segments().last(),
+        Some(ZSegment::Borrowed(messages)) if messages.len() == len - 1
+    ));
+}
+
+#[derive(ZStruct, PartialEq, Debug, Clone)]
+struct ZUnit;
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZUnits<'a> {
+    #[zenoh(size = remain)]
+    pub units: ZSeq<'a, ZUnit>,
+}
+
+#[test]
+fn test_zseq_empty_elements() {
+    // Elements that take no byte would never reach the end of the reader.
+    assert_eq!(
+        <ZUnits as ZStructDecode>::z_decode(&mut [0u8].as_slice()),
+        Err(ZCodecError::CouldNotParse)
+    );
+    assert_eq!(
+        <ZSeq<ZUnit> as ZStructDecode>::z_decode(&mut [2u8, 0, 0].as_slice()),
+        Err(ZCodecError::CouldNotParse)
+    );
+
+    // A count of 2^63 elements is rejected before decoding any of them.
+    let count = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f, 0x01];
+    assert_eq!(
+        <ZSeq<u8> as ZStructDecode>::z_decode(&mut count.as_slice()),
+        Err(ZCodecError::CouldNotRead)
+    );
+}
+
+// Fails to decode once its boundaries have been checked, to observe errors met by `iter`.
+#[derive(PartialEq, Debug, Clone)]
+struct ZFlaky(u8);
+
+static FLAKY_DECODES: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+
+impl<'a> ZStructDecode<'a> for ZFlaky {
+    fn z_decode<R: crate::ZReaderExt<'a>>(r: &mut R) -> crate::ZResult<Self> {
+        let x = r.read_u8()?;
+
+        match FLAKY_DECODES.fetch_add(1, core::sync::atomic::Ordering::Relaxed) {
+            0..3 => Ok(Self(x)),
+            _ => Err(ZCodecError::CouldNotParse),
+        }
+    }
+}
+
+#[test]
+fn test_zseq_iter_error() {
+    let seq = <ZSeq<ZFlaky> as ZStructDecode>::z_decode(&mut [3u8, 1, 2, 3].as_slice()).unwrap();
+
+    let mut iter = seq.iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next(), Some(Err(ZCodecError::CouldNotParse)));
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZHelloOf<S> {
+    pub locators: S,
+    pub sn: u32,
+}
+
+#[test]
+fn test_zseq_from_iter() {
+    let ports = [7447u16, 7448, 80];
+    let x = ZHelloOf {
+        locators: ZSeqFrom::new(ports.iter().zip(LOCATORS).map(|(port, locator)| ZLocator {
+            port: *port,
+            ..locator
+        })),
+        sn: 9,
+    };
+
+    let mut data = [0u8; 64];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
+    let len = <_ as ZStructEncode>::z_len(&x);
+    assert_eq!(len, 64 - writer.len());
+
+    let decoded = <ZHelloOf<ZSeq<ZLocator>> as ZStructDecode>::z_decode(&mut &data[..len]).unwrap();
+    assert_eq!(
+        decoded,
+        ZHelloOf {
+            locators: ZSeq::new(&LOCATORS),
+            sn: 9,
+        }
+    );
+}
@@ -0,0 +1,74 @@
+use crate::{self as zenoh_codec};
+use crate::{ZCodecError, ZStruct, ZStructDecode, ZStructEncode};
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZInner {
+    pub id: u8,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZOuter {
+    #[zenoh(size = prefixed)]
+    pub inner: ZInner,
+    pub sn: u32,
+}
+
+#[test]
+fn test_strict_short_array() {
+    let data = [1u8, 2, 3];
+
+    assert_eq!(
+        <[u8; 4] as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(ZCodecError::CouldNotRead)
+    );
+}
+
+#[test]
+fn test_strict_overlong_vle() {
+    assert_eq!(
+        <u64 as ZStructDecode>::z_decode(&mut [0x80u8, 0x00].as_slice()),
+        Err(ZCodecError::VleOverflow)
+    );
+    assert_eq!(
+        <u64 as ZStructDecode>::z_decode(&mut [0x81u8, 0x80, 0x00].as_slice()),
+        Err(ZCodecError::VleOverflow)
+    );
+
+    assert_eq!(
+        <u64 as ZStructDecode>::z_decode(&mut [0x00u8].as_slice()),
+        Ok(0)
+    );
+    assert_eq!(
+        <u64 as ZStructDecode>::z_decode(&mut [0x80u8, 0x01].as_slice()),
+        Ok(0x80)
+    );
+}
+
+#[test]
+fn test_strict_trailing_bytes() {
+    let msg = ZOuter {
+        inner: ZInner { id: 7 },
+        sn: 42,
+    };
+
+    let mut data = [0u8; 8];
+    let mut writer = data.as_mut_slice();
+    msg.z_encode(&mut writer).unwrap();
+    let len = msg.z_len();
+
+    assert_eq!(
+        <ZOuter as ZStructDecode>::z_decode_exact(&mut &data[..len]),
+        Ok(msg)
+    );
+    assert_eq!(
+        <ZOuter as ZStructDecode>::z_decode_exact(&mut &data[..len + 1]),
+        Err(ZCodecError::TrailingBytes)
+    );
+
+    // The inner struct is prefixed with a length of 2 but only reads 1 byte.
+    let data = [0x02u8, 7, 0, 42];
+    assert_eq!(
+        <ZOuter as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(ZCodecError::TrailingBytes)
+    );
+}
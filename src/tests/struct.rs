@@ -139,7 +139,7 @@ struct ZHeader<'a> {
 macro_rules! roundtrip {
     ($ty:ty, $value:expr) => {{
         let mut data = [0u8; 256];
-        let mut writer = &mut data.as_mut_slice();
+        let mut writer = data.as_mut_slice();
 
         let len = <_ as ZStructEncode>::z_len(&$value);
         <_ as ZStructEncode>::z_encode(&$value, &mut writer).unwrap();
@@ -202,6 +202,29 @@ fn test_znested() {
     roundtrip!(ZNested, s);
 }
 
+// Only `bytes::Bytes` is a byte buffer, other structs named `Bytes` need no size.
+#[derive(ZStruct, PartialEq, Debug)]
+struct Bytes {
+    pub a: u32,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZNamedBytes {
+    pub bytes: Bytes,
+    pub sn: u8,
+}
+
+#[test]
+fn test_znamed_bytes() {
+    roundtrip!(
+        ZNamedBytes,
+        ZNamedBytes {
+            bytes: Bytes { a: 300 },
+            sn: 1,
+        }
+    );
+}
+
 #[test]
 fn test_znested_option() {
     let s = ZNestedOption {
@@ -221,6 +244,63 @@ fn test_zheader_complex() {
         payload: 123456789,
     };
     roundtrip!(ZHeaderComplex, s);
+
+    // S_S holds lengths 1..=4, and SS_S (maybe_empty) lengths 0..=7.
+    roundtrip!(
+        ZHeaderComplex,
+        ZHeaderComplex {
+            maybe_slice: Some(&[1, 2, 3, 4]),
+            maybe_str: Some(""),
+            ..s
+        }
+    );
+
+    let mut data = [0u8; 32];
+    for x in [
+        ZHeaderComplex {
+            maybe_slice: Some(&[]),
+            ..s
+        },
+        ZHeaderComplex {
+            maybe_slice: Some(&[1, 2, 3, 4, 5]),
+            ..s
+        },
+        ZHeaderComplex {
+            maybe_str: Some("12345678"),
+            ..s
+        },
+    ] {
+        assert_eq!(
+            <_ as ZStructEncode>::z_encode(&x, &mut data.as_mut_slice()),
+            Err(crate::ZCodecError::IntegerOutOfRange)
+        );
+    }
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "S:2|_:6")]
+struct ZHeaderSized<'a> {
+    #[zenoh(size = header(S))]
+    pub data: &'a [u8],
+}
+
+#[test]
+fn test_zheader_sized() {
+    roundtrip!(ZHeaderSized, ZHeaderSized { data: &[1] });
+    roundtrip!(
+        ZHeaderSized,
+        ZHeaderSized {
+            data: &[1, 2, 3, 4]
+        }
+    );
+
+    let mut data = [0u8; 8];
+    for x in [ZHeaderSized { data: &[] }, ZHeaderSized { data: &[0; 5] }] {
+        assert_eq!(
+            <_ as ZStructEncode>::z_encode(&x, &mut data.as_mut_slice()),
+            Err(crate::ZCodecError::IntegerOutOfRange)
+        );
+    }
 }
 
 #[test]
@@ -289,3 +369,419 @@ fn test_zheader() {
 
     roundtrip!(ZHeader, header);
 }
+
+#[test]
+fn test_zint_out_of_range() {
+    let mut data = [0u8; 16];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&70000u64, &mut writer).unwrap();
+
+    assert_eq!(
+        <u16 as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(crate::ZCodecError::IntegerOutOfRange)
+    );
+    assert_eq!(
+        <u32 as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Ok(70000)
+    );
+
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&u64::MAX, &mut writer).unwrap();
+
+    assert_eq!(
+        <u32 as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(crate::ZCodecError::IntegerOutOfRange)
+    );
+    assert_eq!(
+        <usize as ZStructDecode>::z_decode(&mut data.as_slice()).is_ok(),
+        usize::BITS == 64
+    );
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "ID:5=0x05|F|_:2=0x1")]
+struct ZIdentified {
+    pub sn: u32,
+}
+
+#[test]
+fn test_zheader_fixed_values() {
+    assert_eq!(ZIdentified::ID, 0x05);
+    assert_eq!(ZIdentified::HEADER_FIXED_MASK, 0b1111_1011);
+    assert!(ZIdentified::matches_header(0b0010_1101));
+    assert!(!ZIdentified::matches_header(0b0011_0001));
+    assert!(!ZIdentified::matches_header(0b0010_1000));
+
+    let mut data = [0u8; 8];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&ZIdentified { sn: 3 }, &mut writer).unwrap();
+    assert_eq!(data[0], 0b0010_1001);
+
+    roundtrip!(ZIdentified, ZIdentified { sn: 3 });
+
+    data[0] = 0b0011_0001;
+    assert_eq!(
+        <ZIdentified as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(crate::ZCodecError::HeaderMismatch)
+    );
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "D:4|_:4")]
+struct ZSigned {
+    #[zenoh(header = D)]
+    pub delta: i8,
+    pub vi8: i8,
+    pub vi16: i16,
+    pub vi32: i32,
+    pub vi64: i64,
+    pub visize: isize,
+}
+
+#[test]
+fn test_zsigned() {
+    let mut data = [0u8; 4];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&-1i64, &mut writer).unwrap();
+    <_ as ZStructEncode>::z_encode(&1i64, &mut writer).unwrap();
+    <_ as ZStructEncode>::z_encode(&-64i64, &mut writer).unwrap();
+    assert_eq!(data, [0x01, 0x02, 0x7f, 0x00]);
+    assert_eq!(<_ as ZStructEncode>::z_len(&64i64), 2);
+
+    let s = ZSigned {
+        delta: -3,
+        vi8: i8::MIN,
+        vi16: -300,
+        vi32: i32::MAX,
+        vi64: i64::MIN,
+        visize: -1,
+    };
+
+    let mut data = [0u8; 32];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&s, &mut writer).unwrap();
+    assert_eq!(data[0], 0b0101_0000);
+
+    roundtrip!(ZSigned, s);
+
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&200i64, &mut writer).unwrap();
+    assert_eq!(
+        <i8 as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(crate::ZCodecError::IntegerOutOfRange)
+    );
+
+    // The 4-bit slot holds zig-zag values up to 15, i.e. -8..=7.
+    roundtrip!(ZSigned, ZSigned { delta: -8, ..s });
+    for delta in [8, -9, i8::MIN] {
+        let mut writer = data.as_mut_slice();
+        assert_eq!(
+            <_ as ZStructEncode>::z_encode(&ZSigned { delta, ..s }, &mut writer),
+            Err(crate::ZCodecError::IntegerOutOfRange)
+        );
+    }
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZFixed {
+    #[zenoh(encoding = le)]
+    pub len: u16,
+    #[zenoh(encoding = be)]
+    pub id: u32,
+    #[zenoh(encoding = le)]
+    pub delta: i64,
+    pub sn: u64,
+}
+
+#[test]
+fn test_zfixed_encoding() {
+    let s = ZFixed {
+        len: 0x0102,
+        id: 0x0304_0506,
+        delta: -2,
+        sn: 1,
+    };
+
+    assert_eq!(<_ as ZStructEncode>::z_len(&s), 2 + 4 + 8 + 1);
+
+    let mut data = [0u8; 15];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&s, &mut writer).unwrap();
+    assert_eq!(
+        data,
+        [
+            0x02, 0x01, 0x03, 0x04, 0x05, 0x06, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x01
+        ]
+    );
+
+    roundtrip!(ZFixed, s);
+
+    assert_eq!(
+        <ZFixed as ZStructDecode>::z_decode(&mut &data[..5]),
+        Err(crate::ZCodecError::CouldNotRead)
+    );
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "R|_:7")]
+struct ZBoolFloat {
+    #[zenoh(header = R)]
+    pub reliable: bool,
+    pub express: bool,
+    pub ratio: f32,
+    pub value: f64,
+}
+
+#[test]
+fn test_zbool_float() {
+    let s = ZBoolFloat {
+        reliable: true,
+        express: false,
+        ratio: 0.5,
+        value: -1.25,
+    };
+
+    assert_eq!(<_ as ZStructEncode>::z_len(&s), 1 + 1 + 4 + 8);
+
+    let mut data = [0u8; 14];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&s, &mut writer).unwrap();
+    assert_eq!(data[0], 0b1000_0000);
+    assert_eq!(data[1], 0);
+    assert_eq!(data[2..6], 0.5f32.to_le_bytes());
+
+    roundtrip!(ZBoolFloat, s);
+
+    data[1] = 2;
+    assert_eq!(
+        <ZBoolFloat as ZStructDecode>::z_decode(&mut data.as_slice()),
+        Err(crate::ZCodecError::CouldNotParse)
+    );
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZSeqNum(u64);
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZPayload<'a>(#[zenoh(size = prefixed)] &'a [u8]);
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "P|F:3|_:4")]
+struct ZTuple<'a>(
+    #[zenoh(header = F)] u8,
+    ZSeqNum,
+    #[zenoh(presence = header(P), size = prefixed)] Option<ZPayload<'a>>,
+);
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZUnit;
+
+#[test]
+fn test_ztuple() {
+    roundtrip!(ZSeqNum, ZSeqNum(300));
+    roundtrip!(ZPayload, ZPayload(&[1, 2, 3]));
+    roundtrip!(ZTuple, ZTuple(5, ZSeqNum(7), Some(ZPayload(&[4; 8]))));
+    roundtrip!(ZTuple, ZTuple(0, ZSeqNum(0), None));
+
+    assert_eq!(<_ as ZStructEncode>::z_len(&ZUnit), 0);
+    assert_eq!(
+        <ZUnit as ZStructDecode>::z_decode(&mut [].as_slice()),
+        Ok(ZUnit)
+    );
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "P|_:7")]
+struct ZEnvelope<'b, P> {
+    sn: ZSeqNum,
+    #[zenoh(presence = header(P), size = prefixed)]
+    payload: Option<P>,
+    #[zenoh(size = remain)]
+    attachment: &'b [u8],
+}
+
+#[test]
+fn test_zgeneric() {
+    roundtrip!(
+        ZEnvelope<ZPayload>,
+        ZEnvelope {
+            sn: ZSeqNum(1),
+            payload: Some(ZPayload(&[1, 2, 3])),
+            attachment: &[4, 5],
+        }
+    );
+    roundtrip!(
+        ZEnvelope<ZBasic>,
+        ZEnvelope {
+            sn: ZSeqNum(2),
+            payload: Some(ZBasic {
+                id: 1,
+                value: 2,
+                array: [3; 4],
+            }),
+            attachment: &[],
+        }
+    );
+    roundtrip!(
+        ZEnvelope<u64>,
+        ZEnvelope::<u64> {
+            sn: ZSeqNum(3),
+            payload: None,
+            attachment: &[6],
+        }
+    );
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "_:3|ID:5=0x19")]
+struct ZInterest<'a> {
+    id: u32,
+    #[zenoh(flags = "K|R|M:2|L:3|_")]
+    options: u8,
+    #[zenoh(header = M)]
+    mode: u8,
+    #[zenoh(header = R)]
+    restricted: bool,
+    #[zenoh(presence = header(K), size = header(L))]
+    key: Option<&'a str>,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZQueryableInfo {
+    #[zenoh(flags = "_:7|C")]
+    flags: u8,
+    #[zenoh(header = C)]
+    complete: bool,
+    distance: u16,
+}
+
+#[test]
+fn test_zflags() {
+    let interest = ZInterest {
+        id: 3,
+        options: 0,
+        mode: 2,
+        restricted: true,
+        key: Some("a/b"),
+    };
+
+    let mut data = [0u8; 16];
+    let mut writer = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&interest, &mut writer).unwrap();
+    assert_eq!(&data[..6], &[0x19, 3, 0b1110_0100, b'a', b'/', b'b']);
+
+    roundtrip!(ZInterest, interest);
+    roundtrip!(
+        ZInterest,
+        ZInterest {
+            id: 4,
+            options: 1,
+            mode: 0,
+            restricted: false,
+            key: None,
+        }
+    );
+
+    roundtrip!(
+        ZQueryableInfo,
+        ZQueryableInfo {
+            flags: 0b1000_0000,
+            complete: true,
+            distance: 300,
+        }
+    );
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+struct ZCounted<'a> {
+    len: u16,
+    sn: u32,
+    #[zenoh(size = field(len))]
+    payload: &'a [u8],
+    has_key: bool,
+    key_len: u8,
+    #[zenoh(presence = field(has_key), size = field(key_len))]
+    key: Option<&'a str>,
+    #[zenoh(size = remain)]
+    tail: &'a [u8],
+}
+
+#[test]
+fn test_zsize_field() {
+    let payload = [7u8; 200];
+    let counted = ZCounted {
+        len: 0,
+        sn: 1,
+        payload: &payload,
+        has_key: false,
+        key_len: 0,
+        key: Some("a/b"),
+        tail: &[9],
+    };
+
+    // The referenced fields are filled on encode.
+    let mut data = [0u8; 256];
+    let mut writer = data.as_mut_slice();
+    let len = <_ as ZStructEncode>::z_len(&counted);
+    <_ as ZStructEncode>::z_encode(&counted, &mut writer).unwrap();
+    assert_eq!(len, 2 + 1 + 200 + 1 + 1 + 3 + 1);
+    assert_eq!(&data[..3], &[0xc8, 0x01, 1]);
+    assert_eq!(&data[203..209], &[1, 3, b'a', b'/', b'b', 9]);
+
+    let decoded = <ZCounted as ZStructDecode>::z_decode(&mut data[..len].as_ref()).unwrap();
+    assert_eq!(
+        decoded,
+        ZCounted {
+            len: 200,
+            has_key: true,
+            key_len: 3,
+            ..counted
+        }
+    );
+
+    roundtrip!(
+        ZCounted,
+        ZCounted {
+            len: 0,
+            sn: 2,
+            payload: &[],
+            has_key: false,
+            key_len: 0,
+            key: None,
+            tail: &[],
+        }
+    );
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "L:3|_:5")]
+struct ZSlotCounted<'a> {
+    #[zenoh(header = L)]
+    len: u8,
+    #[zenoh(size = field(len))]
+    payload: &'a [u8],
+}
+
+#[test]
+fn test_zsize_field_header_slot() {
+    roundtrip!(
+        ZSlotCounted,
+        ZSlotCounted {
+            len: 7,
+            payload: &[1; 7],
+        }
+    );
+
+    // The slot holds up to 7, a longer payload is not truncated to its low bits.
+    let x = ZSlotCounted {
+        len: 0,
+        payload: &[1; 20],
+    };
+    let mut data = [0u8; 32];
+    let mut writer = data.as_mut_slice();
+    assert_eq!(
+        <_ as ZStructEncode>::z_encode(&x, &mut writer),
+        Err(crate::ZCodecError::IntegerOutOfRange)
+    );
+}
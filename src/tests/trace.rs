@@ -0,0 +1,113 @@
+use crate::{self as zenoh_codec};
+use crate::{ZCodecError, ZExt, ZStruct, ZStructDecode, ZStructEncode, ZTrace, ZTraceReader};
+
+#[derive(ZExt, PartialEq, Debug)]
+struct ZExtKeyExpr<'a> {
+    pub sn: u32,
+
+    #[zenoh(size = remain)]
+    pub keyexpr: &'a str,
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "Z|_:7")]
+struct ZTraced<'a> {
+    pub sn: u64,
+
+    #[zenoh(ext = 0x1)]
+    pub ext1: Option<ZExtKeyExpr<'a>>,
+
+    #[zenoh(size = remain)]
+    pub payload: &'a [u8],
+}
+
+fn encode(data: &mut [u8]) -> usize {
+    let x = ZTraced {
+        sn: 5,
+        ext1: Some(ZExtKeyExpr {
+            sn: 1,
+            keyexpr: "a/b",
+        }),
+        payload: &[1, 2, 3],
+    };
+
+    let mut writer = &mut data[..];
+    <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
+    <_ as ZStructEncode>::z_len(&x)
+}
+
+#[test]
+fn test_trace_nested_field() {
+    let mut data = [0u8; 32];
+    let len = encode(&mut data);
+
+    // header, sn, ext header, ext length, ext sn, then the key expression.
+    data[6] = 0xFF;
+
+    let trace = ZTrace::new();
+    let mut reader = ZTraceReader::new(&data[..len], &trace);
+
+    assert_eq!(
+        <ZTraced as ZStructDecode>::z_decode(&mut reader),
+        Err(ZCodecError::InvalidUtf8)
+    );
+    assert_eq!(trace.root(), Some("ZTraced"));
+    assert!(trace.fields().eq(["ext1", "keyexpr"]));
+    assert_eq!(trace.offset(), Some(5));
+
+    #[cfg(feature = "alloc")]
+    {
+        use alloc::string::ToString;
+
+        assert_eq!(trace.to_string(), "ZTraced.ext1.keyexpr at byte 5");
+    }
+}
+
+#[test]
+fn test_trace_truncated() {
+    let mut data = [0u8; 32];
+    let _ = encode(&mut data);
+
+    let trace = ZTrace::new();
+    let mut reader = ZTraceReader::new(&data[..4], &trace);
+
+    assert_eq!(
+        <ZTraced as ZStructDecode>::z_decode(&mut reader),
+        Err(ZCodecError::CouldNotRead)
+    );
+    assert!(trace.fields().eq(["ext1"]));
+    assert_eq!(trace.offset(), Some(2));
+}
+
+#[test]
+fn test_trace_unknown_mandatory_extension() {
+    let mut data = [0u8; 32];
+    let len = encode(&mut data);
+
+    // Turn ext1 into an unknown mandatory extension.
+    data[2] = (data[2] & !0x0F) | 0x10 | 0x7;
+
+    let trace = ZTrace::new();
+    let mut reader = ZTraceReader::new(&data[..len], &trace);
+
+    assert_eq!(
+        <ZTraced as ZStructDecode>::z_decode(&mut reader),
+        Err(ZCodecError::UnknownMandatoryExtension { id: 0x7 })
+    );
+    assert_eq!(trace.root(), Some("ZTraced"));
+    assert_eq!(trace.fields().count(), 0);
+    assert_eq!(trace.offset(), Some(2));
+}
+
+#[test]
+fn test_trace_success_is_empty() {
+    let mut data = [0u8; 32];
+    let len = encode(&mut data);
+
+    let trace = ZTrace::new();
+    let mut reader = ZTraceReader::new(&data[..len], &trace);
+
+    assert!(<ZTraced as ZStructDecode>::z_decode(&mut reader).is_ok());
+    assert!(trace.is_empty());
+    assert_eq!(trace.offset(), None);
+}
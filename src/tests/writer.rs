@@ -0,0 +1,212 @@
+use crate::{self as zenoh_codec};
+use crate::{ZCodecError, ZExt, ZSegment, ZStruct, ZStructEncode, ZVectoredWriter, ZWriterExt};
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "P|_:7")]
+struct ZWritten<'a> {
+    pub sn: u64,
+
+    #[zenoh(presence = header(P), size = prefixed)]
+    pub name: Option<&'a str>,
+
+    #[zenoh(size = remain)]
+    pub payload: &'a [u8],
+}
+
+#[test]
+fn test_slice_writer_too_small() {
+    let x = ZWritten {
+        sn: 1,
+        name: Some("name"),
+        payload: &[0; 32],
+    };
+
+    let mut data = [0u8; 64];
+    let mut writer = &mut data[..16];
+
+    // The whole message is reported, not the payload that did not fit.
+    let Err(ZCodecError::BufferTooSmall { required }) =
+        <_ as ZStructEncode>::z_encode(&x, &mut writer)
+    else {
+        panic!("expected BufferTooSmall");
+    };
+    assert_eq!(required, <_ as ZStructEncode>::z_len(&x));
+
+    let mut writer = &mut data[..required];
+    <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
+    assert!(writer.is_empty());
+}
+
+#[test]
+fn test_slice_writer_slot() {
+    let mut data = [0u8; 4];
+    let mut writer = data.as_mut_slice();
+
+    let slot = writer.write_slot(3, |buf| {
+        buf[0] = 7;
+        1
+    });
+    assert_eq!(slot, Ok(&[7u8][..]));
+    assert_eq!(writer.remaining(), 3);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_vec_writer() {
+    use crate::ZStructDecode;
+    use alloc::vec::Vec;
+
+    let payload = [0xAB; 300];
+    let x = ZWritten {
+        sn: u64::MAX,
+        name: Some("some/name"),
+        payload: &payload,
+    };
+
+    let mut data = Vec::new();
+    <_ as ZStructEncode>::z_encode(&x, &mut data).unwrap();
+    assert_eq!(data.len(), <_ as ZStructEncode>::z_len(&x));
+
+    let mut fixed = [0u8; 512];
+    let mut writer = fixed.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&x, &mut writer).unwrap();
+    assert_eq!(&fixed[..data.len()], data.as_slice());
+
+    let decoded = <ZWritten as ZStructDecode>::z_decode(&mut data.as_slice()).unwrap();
+    assert_eq!(decoded, x);
+}
+
+#[derive(ZExt, PartialEq, Debug)]
+struct ZExtBlob<'a> {
+    #[zenoh(size = prefixed)]
+    pub blob: &'a [u8],
+}
+
+#[derive(ZStruct, PartialEq, Debug)]
+#[zenoh(header = "Z|_:7")]
+struct ZScattered<'a> {
+    #[zenoh(size = prefixed)]
+    pub attachment: &'a [u8],
+
+    #[zenoh(ext = 0x1)]
+    pub ext_blob: Option<ZExtBlob<'a>>,
+
+    #[zenoh(size = remain)]
+    pub payload: &'a [u8],
+}
+
+#[test]
+fn test_vectored_writer() {
+    let attachment = [1u8; 16];
+    let blob = [2u8; 8];
+    let payload = [3u8; 1024];
+
+    let x = ZScattered {
+        attachment: &attachment,
+        ext_blob: Some(ZExtBlob { blob: &blob }),
+        payload: &payload,
+    };
+
+    let mut scratch = [0u8; 32];
+    let mut segments = [ZSegment::default(); 8];
+    let mut writer = ZVectoredWriter::new(&mut scratch, &mut segments);
+    <_ as ZStructEncode>::z_encode_vectored(&x, &mut writer).unwrap();
+
+    assert_eq!(writer.len(), <_ as ZStructEncode>::z_len(&x));
+
+    let borrowed = writer
+        .segments()
+        .iter()
+        .filter_map(|segment| match segment {
+            ZSegment::Borrowed(payload) => Some(payload.as_ptr()),
+            _ => None,
+        });
+    assert!(borrowed.eq([attachment.as_ptr(), blob.as_ptr(), payload.as_ptr()]));
+
+    let mut data = [0u8; 2048];
+    let mut flat = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&x, &mut flat).unwrap();
+
+    let mut expected = &data[..writer.len()];
+    for slice in writer.slices() {
+        let (head, tail) = expected.split_at(slice.len());
+        assert_eq!(slice, head);
+        expected = tail;
+    }
+}
+
+#[test]
+fn test_vectored_writer_too_many_segments() {
+    let x = ZScattered {
+        attachment: &[1],
+        ext_blob: Some(ZExtBlob { blob: &[2] }),
+        payload: &[3],
+    };
+
+    let mut scratch = [0u8; 32];
+    let mut segments = [ZSegment::default(); 4];
+    let mut writer = ZVectoredWriter::new(&mut scratch, &mut segments);
+
+    assert_eq!(
+        <_ as ZStructEncode>::z_encode_vectored(&x, &mut writer),
+        Err(ZCodecError::CouldNotWrite)
+    );
+}
+
+#[test]
+fn test_prefixed_back_patching() {
+    let attachment = [1u8; 200];
+    let blob = [2u8; 300];
+
+    let x = ZScattered {
+        attachment: &attachment,
+        ext_blob: Some(ZExtBlob { blob: &blob }),
+        payload: &[3u8; 4],
+    };
+
+    let mut data = [0u8; 1024];
+    let mut flat = data.as_mut_slice();
+    <_ as ZStructEncode>::z_encode(&x, &mut flat).unwrap();
+
+    let remaining = flat.remaining();
+    let len = data.len() - remaining;
+    assert_eq!(len, <_ as ZStructEncode>::z_len(&x));
+    assert_eq!(&data[1..4], &[200, 1, 1]);
+
+    let decoded = <ZScattered as crate::ZStructDecode>::z_decode(&mut &data[..len]).unwrap();
+    assert_eq!(decoded, x);
+
+    #[cfg(feature = "alloc")]
+    {
+        let mut vec = alloc::vec::Vec::new();
+        <_ as ZStructEncode>::z_encode(&x, &mut vec).unwrap();
+        assert_eq!(vec.as_slice(), &data[..len]);
+    }
+
+    let mut scratch = [0u8; 32];
+    let mut segments = [ZSegment::default(); 8];
+    let mut writer = ZVectoredWriter::new(&mut scratch, &mut segments);
+    <_ as ZStructEncode>::z_encode_vectored(&x, &mut writer).unwrap();
+    assert_eq!(writer.len(), len);
+
+    let mut expected = &data[..len];
+    for slice in writer.slices() {
+        let (head, tail) = expected.split_at(slice.len());
+        assert_eq!(slice, head);
+        expected = tail;
+    }
+}
+
+#[test]
+fn test_prefixed_no_room_for_length() {
+    let x = ZExtBlob { blob: &[0; 130] };
+
+    // The value fits behind a single byte of length, but its length needs two.
+    let mut data = [0u8; 131];
+    let mut writer = data.as_mut_slice();
+
+    assert_eq!(
+        <_ as ZStructEncode>::z_encode(&x, &mut writer),
+        Err(ZCodecError::BufferTooSmall { required: 132 })
+    );
+}
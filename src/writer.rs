@@ -0,0 +1,138 @@
+use crate::{
+    ZCodecError, ZResult,
+    r#struct::uint::{vle_encode, vle_len},
+};
+
+#[cfg(feature = "bytes")]
+mod bytes;
+#[cfg(feature = "alloc")]
+mod vec;
+mod vectored;
+pub use vectored::*;
+
+pub type ZWriter<'a> = &'a mut [u8];
+
+pub trait ZWriterExt {
+    fn remaining(&self) -> usize;
+
+    fn write(&mut self, src: &'_ [u8]) -> ZResult<usize>;
+    fn write_u8(&mut self, value: u8) -> ZResult<()>;
+
+    fn write_exact(&mut self, src: &'_ [u8]) -> ZResult<()>;
+    fn write_slot(
+        &mut self,
+        len: usize,
+        writer: impl FnOnce(&'_ mut [u8]) -> usize,
+    ) -> ZResult<&'_ [u8]>;
+
+    /// Writes whatever `writer` encodes, prefixed with its length as a VLE `usize`.
+    ///
+    /// The length is patched in once the value is written, so it is encoded in a single pass
+    /// instead of calling `z_len` beforehand.
+    fn write_prefixed(&mut self, writer: impl FnOnce(&mut Self) -> ZResult<()>) -> ZResult<()>;
+}
+
+impl<'a> ZWriterExt for ZWriter<'a> {
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    fn write_u8(&mut self, value: u8) -> ZResult<()> {
+        if self.is_empty() {
+            return Err(ZCodecError::BufferTooSmall { required: 1 });
+        }
+
+        unsafe {
+            *self.get_unchecked_mut(0) = value;
+            *self = core::mem::take(self).get_unchecked_mut(1..);
+        }
+
+        Ok(())
+    }
+
+    fn write(&mut self, src: &[u8]) -> ZResult<usize> {
+        if src.is_empty() {
+            return Ok(0);
+        }
+        let len = self.len().min(src.len());
+        if len == 0 {
+            return Err(ZCodecError::BufferTooSmall {
+                required: src.len(),
+            });
+        }
+
+        let (to_write, remain) = unsafe { core::mem::take(self).split_at_mut_unchecked(len) };
+        to_write.copy_from_slice(unsafe { src.get_unchecked(..len) });
+        *self = remain;
+
+        Ok(len)
+    }
+
+    fn write_exact(&mut self, src: &[u8]) -> ZResult<()> {
+        if self.len() < src.len() {
+            return Err(ZCodecError::BufferTooSmall {
+                required: src.len(),
+            });
+        }
+
+        self.write(src).map(|_| ())
+    }
+
+    fn write_slot(
+        &mut self,
+        len: usize,
+        writer: impl FnOnce(&mut [u8]) -> usize,
+    ) -> ZResult<&'_ [u8]> {
+        if self.len() < len {
+            return Err(ZCodecError::BufferTooSmall { required: len });
+        }
+
+        let written = writer(unsafe { self.get_unchecked_mut(..len) });
+
+        if written > len {
+            return Err(ZCodecError::CouldNotWrite);
+        }
+
+        let (slot, remain) = unsafe { core::mem::take(self).split_at_mut_unchecked(written) };
+        *self = remain;
+
+        Ok(slot)
+    }
+
+    fn write_prefixed(&mut self, writer: impl FnOnce(&mut Self) -> ZResult<()>) -> ZResult<()> {
+        // A single byte is reserved for the length, which is enough up to 127 bytes. Longer
+        // values are moved forward afterwards to make room for the extra bytes.
+        if self.is_empty() {
+            return Err(ZCodecError::BufferTooSmall { required: 1 });
+        }
+
+        let total = self.len();
+        let ptr = core::mem::take(self).as_mut_ptr();
+        *self = unsafe { core::slice::from_raw_parts_mut(ptr.add(1), total - 1) };
+
+        writer(self)?;
+
+        let remain = core::mem::take(self).len();
+        let len = total - 1 - remain;
+        let prefix = vle_len(len as u64);
+
+        if prefix - 1 > remain {
+            return Err(ZCodecError::BufferTooSmall {
+                required: prefix + len,
+            });
+        }
+
+        // The writer was a suffix of these `total` bytes and has been taken back.
+        let buffer = unsafe { core::slice::from_raw_parts_mut(ptr, total) };
+        if prefix > 1 {
+            buffer.copy_within(1..1 + len, prefix);
+        }
+
+        unsafe {
+            vle_encode(len as u64, buffer);
+            *self = buffer.get_unchecked_mut(prefix + len..);
+        }
+
+        Ok(())
+    }
+}
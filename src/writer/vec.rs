@@ -0,0 +1,73 @@
+use alloc::vec::Vec;
+
+use crate::{
+    ZCodecError, ZResult, ZWriterExt,
+    r#struct::uint::{vle_encode, vle_len},
+};
+
+/// A growable writer: encoding never fails for lack of space, so there is no need to size the
+/// buffer with `z_len` beforehand.
+impl ZWriterExt for Vec<u8> {
+    fn remaining(&self) -> usize {
+        isize::MAX as usize - self.len()
+    }
+
+    fn write(&mut self, src: &[u8]) -> ZResult<usize> {
+        self.extend_from_slice(src);
+
+        Ok(src.len())
+    }
+
+    fn write_u8(&mut self, value: u8) -> ZResult<()> {
+        self.push(value);
+
+        Ok(())
+    }
+
+    fn write_exact(&mut self, src: &[u8]) -> ZResult<()> {
+        self.extend_from_slice(src);
+
+        Ok(())
+    }
+
+    fn write_slot(
+        &mut self,
+        len: usize,
+        writer: impl FnOnce(&mut [u8]) -> usize,
+    ) -> ZResult<&'_ [u8]> {
+        let start = self.len();
+        self.resize(start + len, 0);
+
+        let written = writer(unsafe { self.get_unchecked_mut(start..) });
+
+        if written > len {
+            self.truncate(start);
+            return Err(ZCodecError::CouldNotWrite);
+        }
+
+        self.truncate(start + written);
+
+        Ok(unsafe { self.get_unchecked(start..) })
+    }
+
+    fn write_prefixed(&mut self, writer: impl FnOnce(&mut Self) -> ZResult<()>) -> ZResult<()> {
+        let start = self.len();
+        self.push(0);
+
+        writer(self)?;
+
+        let len = self.len() - start - 1;
+        let prefix = vle_len(len as u64);
+
+        if prefix > 1 {
+            self.resize(self.len() + prefix - 1, 0);
+            self.copy_within(start + 1..start + 1 + len, start + prefix);
+        }
+
+        unsafe {
+            vle_encode(len as u64, self.get_unchecked_mut(start..));
+        }
+
+        Ok(())
+    }
+}
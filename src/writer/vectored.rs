@@ -0,0 +1,198 @@
+use crate::{
+    ZCodecError, ZResult, ZWriterExt,
+    r#struct::uint::{VLE_LEN_MAX, vle_encode, vle_len},
+};
+
+/// One segment of a vectored encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZSegment<'s> {
+    /// Bytes `start..end` of the scratch buffer of the writer.
+    Scratch(usize, usize),
+    /// A payload referenced without copy.
+    Borrowed(&'s [u8]),
+}
+
+impl Default for ZSegment<'_> {
+    fn default() -> Self {
+        ZSegment::Scratch(0, 0)
+    }
+}
+
+/// A writer that produces a list of segments instead of one contiguous buffer.
+///
+/// Headers and small fields are encoded into a scratch buffer while payloads encoded through
+/// `ZStructEncode::z_encode_vectored` are recorded as borrowed segments, without copy. The
+/// result can be handed to `writev` through [`ZVectoredWriter::slices`].
+#[derive(Debug)]
+pub struct ZVectoredWriter<'s, 'b> {
+    scratch: &'b mut [u8],
+    used: usize,
+
+    segments: &'b mut [ZSegment<'s>],
+    count: usize,
+
+    // Total number of bytes encoded, and the first segment that scratch writes may still grow.
+    len: usize,
+    sealed: usize,
+}
+
+impl<'s, 'b> ZVectoredWriter<'s, 'b> {
+    pub fn new(scratch: &'b mut [u8], segments: &'b mut [ZSegment<'s>]) -> Self {
+        Self {
+            scratch,
+            used: 0,
+            segments,
+            count: 0,
+            len: 0,
+            sealed: 0,
+        }
+    }
+
+    pub fn segments(&self) -> &[ZSegment<'s>] {
+        unsafe { self.segments.get_unchecked(..self.count) }
+    }
+
+    pub fn slices(&self) -> impl Iterator<Item = &[u8]> {
+        self.segments().iter().map(|segment| match segment {
+            ZSegment::Scratch(start, end) => unsafe { self.scratch.get_unchecked(*start..*end) },
+            ZSegment::Borrowed(payload) => payload,
+        })
+    }
+
+    /// Total number of bytes encoded, scratch and borrowed.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn write_borrowed(&mut self, payload: &'s [u8]) -> ZResult<()> {
+        if payload.is_empty() {
+            return Ok(());
+        }
+
+        self.push(ZSegment::Borrowed(payload))?;
+        self.len += payload.len();
+
+        Ok(())
+    }
+
+    fn push(&mut self, segment: ZSegment<'s>) -> ZResult<()> {
+        let slot = self
+            .segments
+            .get_mut(self.count)
+            .ok_or(ZCodecError::CouldNotWrite)?;
+
+        *slot = segment;
+        self.count += 1;
+
+        Ok(())
+    }
+
+    // Marks `len` more bytes of the scratch buffer as written, growing the last segment when
+    // it is contiguous.
+    fn commit(&mut self, len: usize) -> ZResult<()> {
+        let start = self.used;
+        self.used += len;
+        self.len += len;
+
+        if let Some(ZSegment::Scratch(_, end)) = self
+            .count
+            .checked_sub(1)
+            .filter(|last| *last >= self.sealed)
+            .map(|last| unsafe { self.segments.get_unchecked_mut(last) })
+            && *end == start
+        {
+            *end = self.used;
+            return Ok(());
+        }
+
+        self.push(ZSegment::Scratch(start, self.used))
+    }
+}
+
+impl ZWriterExt for ZVectoredWriter<'_, '_> {
+    fn remaining(&self) -> usize {
+        self.scratch.len() - self.used
+    }
+
+    fn write(&mut self, src: &[u8]) -> ZResult<usize> {
+        if src.is_empty() {
+            return Ok(0);
+        }
+
+        let mut free = unsafe { self.scratch.get_unchecked_mut(self.used..) };
+        let len = free.write(src)?;
+        self.commit(len)?;
+
+        Ok(len)
+    }
+
+    fn write_u8(&mut self, value: u8) -> ZResult<()> {
+        let mut free = unsafe { self.scratch.get_unchecked_mut(self.used..) };
+        free.write_u8(value)?;
+
+        self.commit(1)
+    }
+
+    fn write_exact(&mut self, src: &[u8]) -> ZResult<()> {
+        if self.remaining() < src.len() {
+            return Err(ZCodecError::BufferTooSmall {
+                required: src.len(),
+            });
+        }
+
+        self.write(src).map(|_| ())
+    }
+
+    fn write_slot(
+        &mut self,
+        len: usize,
+        writer: impl FnOnce(&mut [u8]) -> usize,
+    ) -> ZResult<&'_ [u8]> {
+        let start = self.used;
+        let written = {
+            let mut free = unsafe { self.scratch.get_unchecked_mut(start..) };
+            free.write_slot(len, writer)?.len()
+        };
+
+        self.commit(written)?;
+
+        Ok(unsafe { self.scratch.get_unchecked(start..start + written) })
+    }
+
+    fn write_prefixed(&mut self, writer: impl FnOnce(&mut Self) -> ZResult<()>) -> ZResult<()> {
+        // The largest length is reserved in a segment of its own, sealed so that the value
+        // does not grow it, and shrunk to the actual length afterwards.
+        if self.remaining() < VLE_LEN_MAX {
+            return Err(ZCodecError::BufferTooSmall {
+                required: VLE_LEN_MAX,
+            });
+        }
+
+        let start = self.used;
+        self.push(ZSegment::Scratch(start, start + VLE_LEN_MAX))?;
+        self.used += VLE_LEN_MAX;
+
+        let reserved = self.count - 1;
+        let sealed = core::mem::replace(&mut self.sealed, self.count);
+        let before = self.len;
+
+        let result = writer(self);
+        self.sealed = sealed;
+        result?;
+
+        let len = (self.len - before) as u64;
+        let prefix = vle_len(len);
+
+        unsafe {
+            vle_encode(len, self.scratch.get_unchecked_mut(start..));
+            *self.segments.get_unchecked_mut(reserved) = ZSegment::Scratch(start, start + prefix);
+        }
+        self.len += prefix;
+
+        Ok(())
+    }
+}